@@ -1,66 +1,384 @@
-use std::fs::File;
-use std::io::{Cursor, Read};
 use byteorder::{LittleEndian, ReadBytesExt};
+use std::fs::File;
+use std::io::{Cursor, Read, Seek};
+
+/// Magic of the pico-qwen checkpoint format ("QWEN" spelled most-significant
+/// byte first; stored little-endian, so the file starts with "NEWQ"),
+/// matching `CHECKPOINT_MAGIC` in `qwen3_inference::configuration`.
+const QWEN_MAGIC: i32 = 0x5157454E;
+/// Magic of the GGUF format ("GGUF" read little-endian as u32).
+const GGUF_MAGIC: u32 = 0x4655_4747;
+
+/// `--json` selects [`OutputFormat::Json`]; the human table is the default
+/// so existing usage (`diagnostic_format model.bin`) keeps working.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OutputFormat {
+    Table,
+    Json,
+}
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args: Vec<String> = std::env::args().collect();
-    if args.len() < 2 {
-        eprintln!("Usage: {} <file.bin>", args[0]);
+    let mut path = None;
+    let mut format = OutputFormat::Table;
+
+    for arg in &args[1..] {
+        match arg.as_str() {
+            "--json" => format = OutputFormat::Json,
+            "--table" => format = OutputFormat::Table,
+            other => path = Some(other.to_string()),
+        }
+    }
+
+    let Some(path) = path else {
+        eprintln!("Usage: {} [--json|--table] <file>", args[0]);
         return Ok(());
+    };
+
+    let mut file = File::open(&path)?;
+    let file_size = file.metadata()?.len();
+    let mut magic_bytes = [0u8; 4];
+    file.read_exact(&mut magic_bytes)?;
+    let magic_i32 = i32::from_le_bytes(magic_bytes);
+    let magic_u32 = u32::from_le_bytes(magic_bytes);
+
+    let report = if magic_u32 == GGUF_MAGIC {
+        inspect_gguf(&mut file)?
+    } else {
+        // The fixed-layout formats share a `[magic:i32][version:i32]` prefix
+        // followed by a handful of `u32`/`f32` fields — bounded well under
+        // the 256-byte header budget, so the (possibly huge) weight section
+        // after it is never read into memory.
+        let mut header = vec![0u8; 256 - 4];
+        file.read_exact(&mut header)?;
+        let mut cursor = Cursor::new(header.as_slice());
+        let version = cursor.read_i32::<LittleEndian>()?;
+
+        match (magic_i32, version) {
+            (QWEN_MAGIC, 1) => inspect_qwen_v1(&mut cursor, file_size)?,
+            (QWEN_MAGIC, other) => Report {
+                format_name: "pico-qwen checkpoint".to_string(),
+                magic: format!("{magic_i32:#x}"),
+                version: other.to_string(),
+                fields: vec![],
+                derived: vec![],
+                warnings: vec![format!(
+                    "Version {other} has no parser yet (only version 1 is implemented); \
+                     header fields beyond magic/version were not read"
+                )],
+            },
+            _ => Report {
+                format_name: "unknown".to_string(),
+                magic: format!("{magic_i32:#x} / {magic_u32:#x}"),
+                version: version.to_string(),
+                fields: vec![],
+                derived: vec![],
+                warnings: vec![format!(
+                    "Magic {magic_i32:#x} matches neither the pico-qwen checkpoint magic \
+                     ({QWEN_MAGIC:#x}) nor GGUF ({GGUF_MAGIC:#x})"
+                )],
+            },
+        }
+    };
+
+    match format {
+        OutputFormat::Table => print_table(&path, &report),
+        OutputFormat::Json => println!("{}", report.to_json()),
     }
 
-    let path = &args[1];
-    let mut file = File::open(path)?;
-    let mut buffer = [0u8; 256];
-    file.read_exact(&mut buffer)?;
+    Ok(())
+}
 
-    let mut cursor = Cursor::new(&buffer);
+/// One inspected field or derived quantity, kept as a string so the table
+/// and JSON renderers share the same data regardless of the source format.
+struct Field {
+    name: String,
+    value: String,
+}
 
+struct Report {
+    format_name: String,
+    magic: String,
+    version: String,
+    fields: Vec<Field>,
+    derived: Vec<Field>,
+    warnings: Vec<String>,
+}
+
+impl Report {
+    fn to_json(&self) -> String {
+        let fields: Vec<String> = self
+            .fields
+            .iter()
+            .map(|f| format!("{:?}: {:?}", f.name, f.value))
+            .collect();
+        let derived: Vec<String> = self
+            .derived
+            .iter()
+            .map(|f| format!("{:?}: {:?}", f.name, f.value))
+            .collect();
+        let warnings: Vec<String> = self.warnings.iter().map(|w| format!("{w:?}")).collect();
+
+        format!(
+            "{{\"format\":{:?},\"magic\":{:?},\"version\":{:?},\"fields\":{{{}}},\"derived\":{{{}}},\"warnings\":[{}]}}",
+            self.format_name,
+            self.magic,
+            self.version,
+            fields.join(","),
+            derived.join(","),
+            warnings.join(",")
+        )
+    }
+}
+
+fn print_table(path: &str, report: &Report) {
     println!("=== Binary Format Diagnostic ===");
-    println!("File: {}", path);
-    
-    // Read the first 36 bytes to check format
-    let magic = cursor.read_i32::<LittleEndian>()?;
-    println!("Magic: {:#x} (expected: {:#x})", magic, 0x5157454E);
-    
-    let version = cursor.read_i32::<LittleEndian>()?;
-    println!("Version: {} (expected: 1)", version);
-    
-    let vocab_size = cursor.read_u32::<LittleEndian>()?;
-    println!("Vocab size: {}", vocab_size);
-    
-    let dim = cursor.read_u32::<LittleEndian>()?;
-    println!("Dimension: {}", dim);
-    
-    let hidden_dim = cursor.read_u32::<LittleEndian>()?;
-    println!("Hidden dim: {}", hidden_dim);
-    
-    let n_layers = cursor.read_u32::<LittleEndian>()?;
-    println!("Layers: {}", n_layers);
-    
-    let n_heads = cursor.read_u32::<LittleEndian>()?;
-    println!("Heads: {}", n_heads);
-    
-    let n_kv_heads = cursor.read_u32::<LittleEndian>()?;
-    println!("KV Heads: {}", n_kv_heads);
-    
-    let seq_len = cursor.read_u32::<LittleEndian>()?;
-    println!("Sequence length: {}", seq_len);
-    
+    println!("File: {path}");
+    println!("Format: {}", report.format_name);
+    println!("Magic: {}", report.magic);
+    println!("Version: {}", report.version);
+
+    if !report.fields.is_empty() {
+        println!("--- Header fields ---");
+        for field in &report.fields {
+            println!("{:<24} {}", format!("{}:", field.name), field.value);
+        }
+    }
+
+    if !report.derived.is_empty() {
+        println!("--- Derived quantities ---");
+        for field in &report.derived {
+            println!("{:<24} {}", format!("{}:", field.name), field.value);
+        }
+    }
+
+    if report.warnings.is_empty() {
+        println!("✅ No inconsistencies detected");
+    } else {
+        for warning in &report.warnings {
+            println!("❌ WARNING: {warning}");
+        }
+    }
+}
+
+/// Parses the version-1 pico-qwen checkpoint header (magic, version, and 8
+/// fixed `u32`/`f32` fields), validates the head-count/dimension
+/// relationships the rest of the crate assumes, and estimates the weight
+/// bytes the header implies so a truncated or mismatched file is obvious
+/// from the file size alone.
+fn inspect_qwen_v1(
+    cursor: &mut Cursor<&[u8]>,
+    file_size: u64,
+) -> Result<Report, Box<dyn std::error::Error>> {
+    let vocab_size = cursor.read_u32::<LittleEndian>()? as u64;
+    let dim = cursor.read_u32::<LittleEndian>()? as u64;
+    let hidden_dim = cursor.read_u32::<LittleEndian>()? as u64;
+    let n_layers = cursor.read_u32::<LittleEndian>()? as u64;
+    let n_heads = cursor.read_u32::<LittleEndian>()? as u64;
+    let n_kv_heads = cursor.read_u32::<LittleEndian>()? as u64;
+    let seq_len = cursor.read_u32::<LittleEndian>()? as u64;
     let rope_theta = cursor.read_f32::<LittleEndian>()?;
-    println!("RoPE theta: {}", rope_theta);
 
-    if magic != 0x5157454E {
-        println!("❌ WARNING: Magic number doesn't match expected QWEN format");
-        println!("   This file may use an older format");
+    let fields = vec![
+        Field { name: "vocab_size".to_string(), value: vocab_size.to_string() },
+        Field { name: "dim".to_string(), value: dim.to_string() },
+        Field { name: "hidden_dim".to_string(), value: hidden_dim.to_string() },
+        Field { name: "n_layers".to_string(), value: n_layers.to_string() },
+        Field { name: "n_heads".to_string(), value: n_heads.to_string() },
+        Field { name: "n_kv_heads".to_string(), value: n_kv_heads.to_string() },
+        Field { name: "seq_len".to_string(), value: seq_len.to_string() },
+        Field { name: "rope_theta".to_string(), value: rope_theta.to_string() },
+    ];
+
+    let mut warnings = Vec::new();
+
+    if n_heads == 0 || dim % n_heads != 0 {
+        warnings.push(format!(
+            "dim ({dim}) is not evenly divisible by n_heads ({n_heads})"
+        ));
+    }
+    if n_heads == 0 || n_kv_heads == 0 || n_heads % n_kv_heads != 0 {
+        warnings.push(format!(
+            "n_heads ({n_heads}) is not evenly divisible by n_kv_heads ({n_kv_heads})"
+        ));
     }
 
-    if version != 1 {
-        println!("❌ WARNING: Version doesn't match expected version 1");
-        println!("   This file may use an older format");
+    let head_dim = if n_heads > 0 { dim / n_heads } else { 0 };
+
+    // Header fields on a corrupted file can be arbitrarily large, so every
+    // product below goes through `checked_mul`, whose overflow degrades to a
+    // warning instead of a debug-build panic; sums use `saturating_add` and
+    // cap at `u64::MAX` without raising that warning.
+    let mut overflowed = false;
+    let mut checked_mul = |a: u64, b: u64| -> u64 {
+        a.checked_mul(b).unwrap_or_else(|| {
+            overflowed = true;
+            0
+        })
+    };
+
+    // Matches the weight layout `ExtendedTransformer` expects per layer:
+    // attention (q/o at dim*dim, k/v at dim*head_dim*n_kv_heads) plus the
+    // SwiGLU feed-forward (three dim*hidden_dim matrices).
+    let qo_params = checked_mul(dim, dim);
+    let kv_heads_dim = checked_mul(head_dim, n_kv_heads);
+    let two_dim = checked_mul(2, dim);
+    let kv_params = checked_mul(two_dim, kv_heads_dim);
+    let attn_params = qo_params.saturating_add(kv_params).saturating_add(qo_params);
+    let ffn_dim = checked_mul(3, dim);
+    let ffn_params = checked_mul(ffn_dim, hidden_dim);
+    let per_layer_params = attn_params.saturating_add(ffn_params);
+    let embedding_dim = checked_mul(2, vocab_size);
+    let embedding_params = checked_mul(embedding_dim, dim);
+    let total_params =
+        checked_mul(n_layers, per_layer_params).saturating_add(embedding_params); // + token embedding and output head
+    let expected_tensor_bytes = checked_mul(total_params, 4); // f32 weights, before quantization
+
+    if overflowed {
+        warnings.push(
+            "derived parameter-count arithmetic overflowed u64 on these header values; \
+             header is likely corrupted, and the derived quantities below are unreliable"
+                .to_string(),
+        );
     }
 
-    println!("✅ File appears to use the unified format");
-    
-    Ok(())
-}
\ No newline at end of file
+    let mut derived = vec![
+        Field { name: "head_dim".to_string(), value: head_dim.to_string() },
+        Field { name: "per_layer_params".to_string(), value: per_layer_params.to_string() },
+        Field { name: "total_params".to_string(), value: total_params.to_string() },
+        Field {
+            name: "expected_tensor_bytes_f32".to_string(),
+            value: expected_tensor_bytes.to_string(),
+        },
+        Field { name: "file_size_bytes".to_string(), value: file_size.to_string() },
+    ];
+
+    // Quantized checkpoints are always smaller than the f32 estimate; only
+    // flag the case that actually indicates truncation or corruption.
+    if file_size < expected_tensor_bytes / 8 {
+        warnings.push(format!(
+            "file size ({file_size} bytes) is far smaller than the smallest \
+             plausible quantization of the header's implied {total_params} \
+             parameters ({expected_tensor_bytes} bytes at f32) — file may be truncated"
+        ));
+    }
+
+    derived.push(Field {
+        name: "size_ratio_vs_f32".to_string(),
+        value: format!("{:.4}", file_size as f64 / expected_tensor_bytes.max(1) as f64),
+    });
+
+    Ok(Report {
+        format_name: "pico-qwen checkpoint".to_string(),
+        magic: format!("{QWEN_MAGIC:#x}"),
+        version: "1".to_string(),
+        fields,
+        derived,
+        warnings,
+    })
+}
+
+/// Parses just enough of a GGUF header to be useful as a diagnostic: magic
+/// (already consumed by the caller), version, tensor count, KV metadata
+/// count, and each metadata key/value pair with its GGUF value type. See
+/// <https://github.com/ggerganov/ggml/blob/master/docs/gguf.md> for the
+/// full format.
+fn inspect_gguf(file: &mut File) -> Result<Report, Box<dyn std::error::Error>> {
+    let version = file.read_u32::<LittleEndian>()?;
+    let tensor_count = file.read_u64::<LittleEndian>()?;
+    let metadata_kv_count = file.read_u64::<LittleEndian>()?;
+
+    let mut fields = vec![
+        Field { name: "tensor_count".to_string(), value: tensor_count.to_string() },
+        Field { name: "metadata_kv_count".to_string(), value: metadata_kv_count.to_string() },
+    ];
+
+    let mut warnings = Vec::new();
+    for i in 0..metadata_kv_count {
+        match read_gguf_kv(file) {
+            Ok((key, value)) => fields.push(Field { name: key, value }),
+            Err(err) => {
+                warnings.push(format!(
+                    "failed to read metadata entry {i} of {metadata_kv_count}: {err}"
+                ));
+                break;
+            }
+        }
+    }
+
+    Ok(Report {
+        format_name: "GGUF".to_string(),
+        magic: format!("{GGUF_MAGIC:#x}"),
+        version: version.to_string(),
+        fields,
+        derived: vec![],
+        warnings,
+    })
+}
+
+/// A GGUF string length is attacker/corruption-controlled; this tool exists
+/// to diagnose exactly the kind of truncated or hand-edited file that would
+/// carry a bogus length, so it must never be used to size an allocation
+/// before being sanity-checked.
+const MAX_GGUF_STRING_LEN: u64 = 1024 * 1024;
+
+fn read_gguf_string(file: &mut File) -> Result<String, Box<dyn std::error::Error>> {
+    let len = file.read_u64::<LittleEndian>()?;
+    let remaining = file
+        .metadata()?
+        .len()
+        .saturating_sub(file.stream_position()?);
+    if len > MAX_GGUF_STRING_LEN || len > remaining {
+        return Err(format!(
+            "GGUF string length {len} exceeds the sane cap ({MAX_GGUF_STRING_LEN} bytes) \
+             or the {remaining} bytes remaining in the file — file is likely truncated or corrupted"
+        )
+        .into());
+    }
+    let mut bytes = vec![0u8; len as usize];
+    file.read_exact(&mut bytes)?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+fn read_gguf_kv(file: &mut File) -> Result<(String, String), Box<dyn std::error::Error>> {
+    let key = read_gguf_string(file)?;
+    let value = read_gguf_value(file)?;
+    Ok((key, value))
+}
+
+/// Reads one typed GGUF value, given its `value_type` tag, as a display
+/// string; arrays recurse into their element type.
+fn read_gguf_value(file: &mut File) -> Result<String, Box<dyn std::error::Error>> {
+    let value_type = file.read_u32::<LittleEndian>()?;
+    read_gguf_value_of_type(file, value_type)
+}
+
+fn read_gguf_value_of_type(
+    file: &mut File,
+    value_type: u32,
+) -> Result<String, Box<dyn std::error::Error>> {
+    Ok(match value_type {
+        0 => file.read_u8()?.to_string(),
+        1 => file.read_i8()?.to_string(),
+        2 => file.read_u16::<LittleEndian>()?.to_string(),
+        3 => file.read_i16::<LittleEndian>()?.to_string(),
+        4 => file.read_u32::<LittleEndian>()?.to_string(),
+        5 => file.read_i32::<LittleEndian>()?.to_string(),
+        6 => file.read_f32::<LittleEndian>()?.to_string(),
+        7 => (file.read_u8()? != 0).to_string(),
+        8 => format!("{:?}", read_gguf_string(file)?),
+        9 => {
+            let element_type = file.read_u32::<LittleEndian>()?;
+            let len = file.read_u64::<LittleEndian>()?;
+            let mut elements = Vec::with_capacity(len.min(64) as usize);
+            for _ in 0..len {
+                elements.push(read_gguf_value_of_type(file, element_type)?);
+            }
+            format!("[{}]", elements.join(", "))
+        }
+        10 => file.read_u64::<LittleEndian>()?.to_string(),
+        11 => file.read_i64::<LittleEndian>()?.to_string(),
+        12 => file.read_f64::<LittleEndian>()?.to_string(),
+        other => return Err(format!("unknown GGUF value type {other}").into()),
+    })
+}
@@ -8,14 +8,64 @@ pub struct Config {
     pub models: ModelsConfig,
     pub limits: LimitsConfig,
     pub logging: LoggingConfig,
+    #[serde(default)]
+    pub telemetry: TelemetryConfig,
+    #[serde(default)]
+    pub resource_budget: ResourceBudget,
+    #[serde(default)]
+    pub admin: AdminConfig,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct ServerConfig {
+    /// An IP address for a TCP listener, or `unix:/path/to/socket.sock` to
+    /// bind a Unix domain socket there instead (see `crate::listener`).
+    /// `port` is ignored in the Unix case.
     pub bind_address: String,
     pub port: u16,
     pub cors_origins: Vec<String>,
     pub request_timeout: u64,
+    /// Only meaningful for a `unix:` `bind_address`: remove a stale socket
+    /// file left behind at that path before binding, and delete it again on
+    /// shutdown. Ignored for TCP.
+    #[serde(default)]
+    pub reuse: bool,
+    /// Accept-Encoding-negotiated response compression, applied to every
+    /// route except already-upgraded WebSocket connections.
+    #[serde(default)]
+    pub compression: CompressionConfig,
+}
+
+/// Governs the streaming gzip/deflate layer `Server::create_router` wraps
+/// every response in. Responses smaller than `min_size_bytes` are left
+/// uncompressed, since the framing overhead isn't worth it for e.g. a
+/// one-line health check body.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct CompressionConfig {
+    #[serde(default = "default_compression_min_size_bytes")]
+    pub min_size_bytes: usize,
+    #[serde(default = "default_true")]
+    pub gzip: bool,
+    #[serde(default = "default_true")]
+    pub deflate: bool,
+}
+
+impl Default for CompressionConfig {
+    fn default() -> Self {
+        Self {
+            min_size_bytes: default_compression_min_size_bytes(),
+            gzip: true,
+            deflate: true,
+        }
+    }
+}
+
+fn default_compression_min_size_bytes() -> usize {
+    256
+}
+
+fn default_true() -> bool {
+    true
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -24,6 +74,18 @@ pub struct ModelsConfig {
     pub max_loaded_models: usize,
     pub default_quantization: String,
     pub context_window: usize,
+    /// Model id to load at startup so `/api/v1/generate` and `/api/v1/chat`
+    /// are immediately usable instead of 404ing until a client calls the
+    /// load-model endpoint. `#[serde(default)]` so
+    /// existing config files without this field keep parsing.
+    #[serde(default)]
+    pub default_model: Option<String>,
+    /// Total bytes all loaded models' `ModelInfo.size` may sum to before
+    /// `enforce_model_limits` starts evicting least-recently-used models to
+    /// make room, in addition to the `max_loaded_models` count cap. `None`
+    /// disables the budget check, leaving eviction purely count-based.
+    #[serde(default)]
+    pub max_total_memory_bytes: Option<u64>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -39,6 +101,84 @@ pub struct LoggingConfig {
     pub format: String,
 }
 
+/// Observability settings. `/metrics` always exposes the Prometheus text
+/// format regardless of this block; `otlp_endpoint` additionally opts into
+/// pushing the same metrics to an OTLP collector for stacks that don't
+/// scrape.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct TelemetryConfig {
+    /// e.g. `http://localhost:4317`. `None` (the default) disables the OTLP
+    /// exporter entirely, leaving `/metrics` as the only integration point.
+    #[serde(default)]
+    pub otlp_endpoint: Option<String>,
+    /// `service.name` resource attribute reported to the OTLP collector.
+    #[serde(default = "default_service_name")]
+    pub service_name: String,
+}
+
+impl Default for TelemetryConfig {
+    fn default() -> Self {
+        Self {
+            otlp_endpoint: None,
+            service_name: default_service_name(),
+        }
+    }
+}
+
+fn default_service_name() -> String {
+    "pico-qwen-api".to_string()
+}
+
+/// Thresholds `/api/v1/health` checks against the `ResourceMonitor`
+/// snapshot to decide whether to report `"degraded"` instead of always
+/// `"healthy"`.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ResourceBudget {
+    /// Fraction of total system memory in use (0.0-1.0) above which
+    /// `/api/v1/health` reports `"degraded"`.
+    #[serde(default = "default_memory_pressure_threshold")]
+    pub memory_pressure_threshold: f32,
+}
+
+impl Default for ResourceBudget {
+    fn default() -> Self {
+        Self {
+            memory_pressure_threshold: default_memory_pressure_threshold(),
+        }
+    }
+}
+
+fn default_memory_pressure_threshold() -> f32 {
+    0.9
+}
+
+/// Gates the `/admin/*` surface (runtime model load/unload/inspect and cloud
+/// config hot-reload) behind a bearer token, and seeds the `CloudManager`
+/// that `PUT /admin/config` replaces at runtime.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct AdminConfig {
+    /// Bearer token `/admin/*` compares against the `Authorization` header.
+    /// `None` (the default) disables the whole admin API — every request
+    /// under `/admin/*` 404s rather than accepting an empty credential.
+    #[serde(default)]
+    pub token: Option<String>,
+    /// Multi-provider cloud routing config to build the initial
+    /// `CloudManager` from at startup. `PUT /admin/config` replaces it at
+    /// runtime without a restart; `None` leaves cloud routing unconfigured
+    /// until the first reload.
+    #[serde(default)]
+    pub cloud: Option<qwen3_inference::cloud::CloudConfig>,
+}
+
+impl Default for AdminConfig {
+    fn default() -> Self {
+        Self {
+            token: None,
+            cloud: None,
+        }
+    }
+}
+
 impl Config {
     pub fn load(path: impl AsRef<Path>) -> Result<Self> {
         let config_str = std::fs::read_to_string(path)?;
@@ -53,12 +193,16 @@ impl Config {
                 port: 8080,
                 cors_origins: vec!["*".to_string()],
                 request_timeout: 30,
+                reuse: false,
+                compression: CompressionConfig::default(),
             },
             models: ModelsConfig {
                 directory: "./models".to_string(),
                 max_loaded_models: 2,
                 default_quantization: "int8".to_string(),
                 context_window: 4096,
+                default_model: None,
+                max_total_memory_bytes: None,
             },
             limits: LimitsConfig {
                 max_request_size: 1024 * 1024, // 1MB
@@ -69,6 +213,9 @@ impl Config {
                 level: "info".to_string(),
                 format: "json".to_string(),
             },
+            telemetry: TelemetryConfig::default(),
+            resource_budget: ResourceBudget::default(),
+            admin: AdminConfig::default(),
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,75 @@
+use axum::{http::StatusCode, response::IntoResponse, response::Response, Json};
+use serde_json::json;
+use thiserror::Error;
+
+/// Central error type for the HTTP/WebSocket surface. Every variant maps to
+/// both an HTTP status and a stable, machine-readable `code` (via
+/// [`ApiError::code`]) so clients can branch on error kind instead of
+/// parsing the English `message`.
+#[derive(Debug, Error)]
+pub enum ApiError {
+    #[error("model not found: {0}")]
+    ModelNotFound(String),
+    #[error("limit exceeded: {0}")]
+    LimitExceeded(String),
+    #[error("upstream unavailable: {0}")]
+    UpstreamUnavailable(String),
+    #[error("bad request: {0}")]
+    BadRequest(String),
+    #[error("internal error: {0}")]
+    Internal(String),
+}
+
+impl ApiError {
+    /// Stable identifier carried as `error.code` in the JSON body, distinct
+    /// from the human-readable `message` so it survives message wording
+    /// changes.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ApiError::ModelNotFound(_) => "model_not_found",
+            ApiError::LimitExceeded(_) => "limit_exceeded",
+            ApiError::UpstreamUnavailable(_) => "upstream_unavailable",
+            ApiError::BadRequest(_) => "bad_request",
+            ApiError::Internal(_) => "internal_error",
+        }
+    }
+
+    fn status(&self) -> StatusCode {
+        match self {
+            ApiError::ModelNotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::LimitExceeded(_) => StatusCode::TOO_MANY_REQUESTS,
+            ApiError::UpstreamUnavailable(_) => StatusCode::BAD_GATEWAY,
+            ApiError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            ApiError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    /// The `{ "error": { "code", "message" } }` body shared by the HTTP
+    /// `IntoResponse` impl and the WebSocket `error` action.
+    pub fn body(&self) -> serde_json::Value {
+        json!({
+            "error": {
+                "code": self.code(),
+                "message": self.to_string(),
+            }
+        })
+    }
+}
+
+impl From<anyhow::Error> for ApiError {
+    fn from(err: anyhow::Error) -> Self {
+        ApiError::Internal(err.to_string())
+    }
+}
+
+impl From<std::io::Error> for ApiError {
+    fn from(err: std::io::Error) -> Self {
+        ApiError::Internal(err.to_string())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.status(), Json(self.body())).into_response()
+    }
+}
@@ -0,0 +1,151 @@
+use crate::handlers::models::{LoadModelRequest, LoadModelResponse, UnloadModelResponse};
+use crate::state::AppState;
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+/// Checks the `Authorization: Bearer <token>` header against
+/// `config.admin.token`. Returns 404 rather than 401 when no token is
+/// configured, so a server that hasn't opted into the admin API doesn't
+/// even reveal that one exists.
+fn authorize(state: &AppState, headers: &HeaderMap) -> Result<(), (StatusCode, String)> {
+    let Some(expected) = state.config.admin.token.as_deref() else {
+        return Err((StatusCode::NOT_FOUND, "Not Found".to_string()));
+    };
+
+    let provided = headers
+        .get(axum::http::header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+
+    if provided.is_some_and(|provided| constant_time_eq(provided.as_bytes(), expected.as_bytes())) {
+        Ok(())
+    } else {
+        Err((
+            StatusCode::UNAUTHORIZED,
+            "Missing or invalid admin bearer token".to_string(),
+        ))
+    }
+}
+
+/// Compares two byte strings without branching on the first mismatched byte,
+/// so the admin token (used to gate model load/unload and cloud-provider
+/// credential rotation) can't be recovered via a timing side-channel.
+/// Short-circuits only on length, which isn't secret.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b.iter()).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// `POST /admin/models/:model_id` — load a model the same way the public
+/// load endpoint does, once the bearer token checks out.
+pub async fn load_model(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(model_id): Path<String>,
+    Json(request): Json<LoadModelRequest>,
+) -> Result<Json<LoadModelResponse>, Response> {
+    authorize(&state, &headers).map_err(IntoResponse::into_response)?;
+    crate::handlers::models::load_model(Path(model_id), State(state), Json(request))
+        .await
+        .map_err(IntoResponse::into_response)
+}
+
+/// `DELETE /admin/models/:model_id` — unload a model.
+pub async fn unload_model(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(model_id): Path<String>,
+) -> Result<Json<UnloadModelResponse>, Response> {
+    authorize(&state, &headers).map_err(IntoResponse::into_response)?;
+    crate::handlers::models::unload_model(Path(model_id), State(state))
+        .await
+        .map_err(IntoResponse::into_response)
+}
+
+/// Detailed per-model stats for `GET /admin/models/:model_id`, beyond what
+/// the read-only `/api/v1/models` listing exposes.
+#[derive(Debug, Serialize)]
+pub struct ModelAdminStats {
+    pub id: String,
+    pub request_count: u64,
+    pub total_tokens_generated: u64,
+    pub loaded_at: Duration,
+    pub last_inference_at: Option<Duration>,
+}
+
+/// `GET /admin/models/:model_id` — detailed stats for one loaded model.
+pub async fn model_details(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(model_id): Path<String>,
+) -> Result<Json<ModelAdminStats>, (StatusCode, String)> {
+    authorize(&state, &headers)?;
+
+    let entry = state.models.get(&model_id).ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            format!("Model '{model_id}' is not loaded"),
+        )
+    })?;
+
+    let now = Instant::now();
+    Ok(Json(ModelAdminStats {
+        id: entry.info.id.clone(),
+        request_count: entry
+            .request_count
+            .load(std::sync::atomic::Ordering::Relaxed),
+        total_tokens_generated: entry
+            .total_tokens_generated
+            .load(std::sync::atomic::Ordering::Relaxed),
+        loaded_at: now.duration_since(entry.loaded_at),
+        last_inference_at: entry.last_inference_at.map(|t| now.duration_since(t)),
+    }))
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReloadCloudConfigResponse {
+    pub success: bool,
+    pub providers: Vec<String>,
+    pub message: String,
+}
+
+/// `PUT /admin/config` — rebuilds the `CloudManager` from the posted
+/// `CloudConfig` and swaps it in, so operators can rotate provider API keys
+/// or change the provider list without restarting the server. The old
+/// manager's background health-check loop is aborted when it's dropped.
+pub async fn reload_cloud_config(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(config): Json<qwen3_inference::cloud::CloudConfig>,
+) -> Result<Json<ReloadCloudConfigResponse>, (StatusCode, String)> {
+    authorize(&state, &headers)?;
+
+    let providers = config
+        .providers
+        .iter()
+        .map(|provider| provider.name.clone())
+        .collect();
+
+    let manager = qwen3_inference::cloud::CloudManager::new(config).map_err(|err| {
+        (
+            StatusCode::BAD_REQUEST,
+            format!("Failed to build cloud manager: {err}"),
+        )
+    })?;
+
+    *state.cloud_manager.write().await = Some(manager);
+
+    Ok(Json(ReloadCloudConfigResponse {
+        success: true,
+        providers,
+        message: "Cloud provider configuration reloaded".to_string(),
+    }))
+}
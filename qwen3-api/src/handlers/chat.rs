@@ -1,7 +1,20 @@
+use crate::handlers::generation_support::{cache_pos, sliding_window_params};
 use crate::state::AppState;
-use axum::{extract::State, Json};
+use axum::{
+    extract::State,
+    response::{
+        sse::{Event, Sse},
+        IntoResponse, Response,
+    },
+    Json,
+};
+use futures_core::Stream;
 use qwen3_inference::{sampler::Sampler, tokenizer::Tokenizer};
 use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 
 #[derive(Debug, Deserialize)]
 pub struct ChatRequest {
@@ -38,10 +51,89 @@ pub struct Usage {
     pub total_tokens: usize,
 }
 
+/// One `chat.completion.chunk` SSE event, OpenAI-shaped so existing
+/// streaming clients decode it without special-casing this server.
+#[derive(Debug, Serialize)]
+struct ChatCompletionChunk {
+    object: &'static str,
+    choices: Vec<ChatChunkChoice>,
+}
+
+#[derive(Debug, Serialize)]
+struct ChatChunkChoice {
+    delta: ChatChunkDelta,
+    finish_reason: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize)]
+struct ChatChunkDelta {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    role: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content: Option<String>,
+}
+
+/// Response type for `chat_handler`: a buffered `ChatResponse` when
+/// `stream` isn't set, or an SSE stream of `chat.completion.chunk` events
+/// when it is. Axum handlers must return a single concrete type, so the two
+/// paths are unified behind one `IntoResponse` impl rather than boxing the
+/// whole response.
+pub enum ChatHandlerResponse {
+    Buffered(Json<ChatResponse>),
+    Streamed(Sse<Pin<Box<dyn Stream<Item = Result<Event, Infallible>> + Send>>>),
+}
+
+impl IntoResponse for ChatHandlerResponse {
+    fn into_response(self) -> Response {
+        match self {
+            ChatHandlerResponse::Buffered(json) => json.into_response(),
+            ChatHandlerResponse::Streamed(sse) => sse.into_response(),
+        }
+    }
+}
+
+/// Decrements `active_requests` and folds whatever completion-token count
+/// was reached into the model's stats, no matter whether the stream ran to
+/// completion or the client disconnected mid-generation. Held for the
+/// lifetime of the streaming generator so `Drop` fires exactly once, at
+/// whichever point the stream itself stops being polled.
+struct StreamCompletionGuard {
+    state: AppState,
+    model_id: String,
+    completion_tokens: Arc<AtomicU64>,
+    started_at: std::time::Instant,
+}
+
+impl Drop for StreamCompletionGuard {
+    fn drop(&mut self) {
+        let completion_tokens = self.completion_tokens.load(Ordering::Relaxed);
+        if let Some(mut model) = self.state.models.get_mut(&self.model_id) {
+            model.request_count.fetch_add(1, Ordering::Relaxed);
+            model
+                .total_tokens_generated
+                .fetch_add(completion_tokens, Ordering::Relaxed);
+            model.last_inference_at = Some(std::time::Instant::now());
+        }
+        self.state
+            .active_requests
+            .fetch_sub(1, Ordering::Relaxed);
+
+        crate::metrics::REQUESTS_TOTAL
+            .with_label_values(&[&self.model_id])
+            .inc();
+        crate::metrics::TOKENS_GENERATED_TOTAL
+            .with_label_values(&[&self.model_id])
+            .inc_by(completion_tokens);
+        crate::metrics::GENERATION_LATENCY_SECONDS
+            .with_label_values(&[&self.model_id])
+            .observe(self.started_at.elapsed().as_secs_f64());
+    }
+}
+
 pub async fn chat_handler(
     State(state): State<AppState>,
     Json(request): Json<ChatRequest>,
-) -> Result<Json<ChatResponse>, (axum::http::StatusCode, String)> {
+) -> Result<ChatHandlerResponse, (axum::http::StatusCode, String)> {
     // Increment active requests
     state
         .active_requests
@@ -58,6 +150,62 @@ pub async fn chat_handler(
     // Format messages into a prompt
     let prompt = format_messages(&request.messages);
 
+    if request.stream == Some(true) {
+        let (batch_permit, reservation) =
+            match admit_request(&model, &request, &prompt).await {
+                Ok(admission) => admission,
+                Err(err) => {
+                    state
+                        .active_requests
+                        .fetch_sub(1, std::sync::atomic::Ordering::Relaxed);
+                    return Err(err);
+                }
+            };
+
+        return Ok(ChatHandlerResponse::Streamed(stream_chat_response(
+            state,
+            model,
+            request,
+            prompt,
+            batch_permit,
+            reservation,
+        )));
+    }
+
+    let (batch_permit, reservation) = match admit_request(&model, &request, &prompt).await {
+        Ok(admission) => admission,
+        Err(local_err) => {
+            let cloud_config = model.transformer.read().await.config.cloud_config.clone();
+            let Some(cloud_config) = cloud_config else {
+                state
+                    .active_requests
+                    .fetch_sub(1, std::sync::atomic::Ordering::Relaxed);
+                return Err(local_err);
+            };
+
+            let timer = crate::metrics::GenerationTimer::start(&request.model);
+            let result = generate_via_cloud(&model, &cloud_config, &request, &prompt).await;
+
+            if let Some(mut model_stats) = state.models.get_mut(&request.model) {
+                model_stats
+                    .request_count
+                    .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                model_stats.last_inference_at = Some(std::time::Instant::now());
+            }
+            state
+                .active_requests
+                .fetch_sub(1, std::sync::atomic::Ordering::Relaxed);
+            timer.finish(
+                result
+                    .as_ref()
+                    .map(|r| r.usage.completion_tokens as u64)
+                    .unwrap_or(0),
+            );
+
+            return result.map(|response| ChatHandlerResponse::Buffered(Json(response)));
+        }
+    };
+
     // Update last used time and generate
     {
         let mut transformer = model.transformer.write().await;
@@ -67,6 +215,8 @@ pub async fn chat_handler(
         let temperature = request.temperature.unwrap_or(0.7);
         let top_p = request.top_p.unwrap_or(0.9);
 
+        let context_management = transformer.config.inference_params.context_management.clone();
+
         // Get underlying transformer and tokenizer
         let transformer = transformer.transformer_mut();
         let tokenizer = Tokenizer::new(
@@ -83,14 +233,27 @@ pub async fn chat_handler(
             42, // seed
         );
 
+        let timer = crate::metrics::GenerationTimer::start(&request.model);
+
         // Generate response using API-friendly generation
-        let response_text =
-            generate_api_response(transformer, &tokenizer, &mut sampler, &prompt, max_tokens)
-                .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        let response_text = generate_api_response(
+            transformer,
+            &tokenizer,
+            &mut sampler,
+            &prompt,
+            max_tokens,
+            &context_management,
+        )
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
         // Count tokens
         let prompt_tokens = tokenizer.encode(&prompt).len();
         let completion_tokens = tokenizer.encode(&response_text).len();
+        timer.finish(completion_tokens as u64);
+        // Reservation/permit are estimate-only admission control; drop them
+        // once generation is complete rather than holding them past this block.
+        drop(reservation);
+        drop(batch_permit);
 
         let response = ChatResponse {
             choices: vec![ChatChoice {
@@ -122,16 +285,291 @@ pub async fn chat_handler(
         state
             .active_requests
             .fetch_sub(1, std::sync::atomic::Ordering::Relaxed);
-        Ok(Json(response))
+        Ok(ChatHandlerResponse::Buffered(Json(response)))
     }
 }
 
+/// Admission control shared by both the buffered and streaming paths: caps
+/// concurrent generations per model at `memory_limits.max_batch_size` and
+/// reserves this request's estimated KV-cache bytes against
+/// `memory_limits.max_memory_mb` before any tokens are generated. Returns
+/// `503 Service Unavailable` (with a human-readable deficit) rather than
+/// letting the request proceed into an OOM.
+async fn admit_request(
+    model: &crate::state::LoadedModel,
+    request: &ChatRequest,
+    prompt: &str,
+) -> Result<
+    (tokio::sync::OwnedSemaphorePermit, qwen3_inference::Reservation),
+    (axum::http::StatusCode, String),
+> {
+    let (memory_limits, n_layers, n_kv_heads, head_dim, quantization, vocab_size) = {
+        let transformer = model.transformer.read().await;
+        (
+            transformer.config.memory_limits,
+            transformer.config.base.n_layers,
+            transformer.config.base.n_kv_heads,
+            transformer.config.base.head_dim,
+            transformer.config.quantization,
+            transformer.config.base.vocab_size,
+        )
+    };
+
+    let batch_permit = model
+        .batch_semaphore
+        .clone()
+        .try_acquire_owned()
+        .map_err(|_| {
+            (
+                axum::http::StatusCode::SERVICE_UNAVAILABLE,
+                format!(
+                    "Model '{}' is at its concurrency limit (max_batch_size = {})",
+                    request.model, memory_limits.max_batch_size
+                ),
+            )
+        })?;
+
+    let tokenizer = Tokenizer::new(&model.info.path.to_string_lossy(), vocab_size, false)
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let prompt_token_count = tokenizer.encode(prompt).len();
+    let max_tokens = request.max_tokens.unwrap_or(100);
+    let effective_seq_len =
+        (prompt_token_count + max_tokens).min(memory_limits.max_context_length);
+    let kv_cache_bytes = qwen3_inference::estimate_kv_cache_bytes(
+        n_layers,
+        n_kv_heads,
+        head_dim,
+        effective_seq_len,
+        quantization,
+    );
+
+    let reservation = model
+        .memory_limiter
+        .try_reserve(kv_cache_bytes)
+        .ok_or_else(|| memory_unavailable_response(&model.memory_limiter, kv_cache_bytes))?;
+
+    Ok((batch_permit, reservation))
+}
+
+/// Dispatches to the model's configured cloud provider when local admission
+/// refused the request (the model's concurrency cap or KV-cache memory
+/// budget was hit, or its tokenizer failed to load). Retries are spent inside [`qwen3_inference::cloud::generate_with_retries`]
+/// per `cloud_config.retries`; once those are exhausted the provider's own
+/// error is surfaced as `502 Bad Gateway` rather than a generic 500, so
+/// callers can tell a cloud failure apart from a server bug.
+async fn generate_via_cloud(
+    model: &crate::state::LoadedModel,
+    cloud_config: &qwen3_inference::CloudConfig,
+    request: &ChatRequest,
+    prompt: &str,
+) -> Result<ChatResponse, (axum::http::StatusCode, String)> {
+    use qwen3_inference::cloud::{build_http_client, build_provider, generate_with_retries, InferenceConfig};
+
+    let provider_config: qwen3_inference::cloud::CloudProviderConfig = cloud_config.into();
+    let client = build_http_client(provider_config.timeout)
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let provider = build_provider(provider_config, client)
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let inference_config = InferenceConfig {
+        max_tokens: request.max_tokens.unwrap_or(cloud_config.max_tokens),
+        temperature: request.temperature.unwrap_or(0.7),
+        top_p: request.top_p.unwrap_or(0.9),
+        frequency_penalty: 0.0,
+        presence_penalty: 0.0,
+    };
+
+    let content = generate_with_retries(&provider, prompt, &inference_config)
+        .await
+        .map_err(|e| (axum::http::StatusCode::BAD_GATEWAY, e.to_string()))?;
+
+    // Cloud providers don't report token counts in a uniform shape, so the
+    // local tokenizer is reused here purely for `Usage` accounting.
+    let vocab_size = model.transformer.read().await.config.base.vocab_size;
+    let tokenizer = Tokenizer::new(&model.info.path.to_string_lossy(), vocab_size, false)
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let prompt_tokens = tokenizer.encode(prompt).len();
+    let completion_tokens = tokenizer.encode(&content).len();
+
+    Ok(ChatResponse {
+        choices: vec![ChatChoice {
+            message: ChatMessage {
+                role: "assistant".to_string(),
+                content: content.trim().to_string(),
+            },
+            finish_reason: Some("stop".to_string()),
+        }],
+        usage: Usage {
+            prompt_tokens,
+            completion_tokens,
+            total_tokens: prompt_tokens + completion_tokens,
+        },
+    })
+}
+
+fn memory_unavailable_response(
+    limiter: &qwen3_inference::MemoryLimiter,
+    requested_bytes: u64,
+) -> (axum::http::StatusCode, String) {
+    let requested_mib = requested_bytes as f64 / (1024.0 * 1024.0);
+    let available_mib = limiter
+        .capacity_bytes()
+        .saturating_sub(limiter.reserved_bytes()) as f64
+        / (1024.0 * 1024.0);
+    (
+        axum::http::StatusCode::SERVICE_UNAVAILABLE,
+        format!("requested {requested_mib:.1} MiB, {available_mib:.1} MiB available"),
+    )
+}
+
+/// Builds the SSE stream for `stream: true` requests: generates one token
+/// at a time against the live transformer, decoding and yielding each as a
+/// `chat.completion.chunk` as soon as it's sampled (no buffering the whole
+/// completion first), then a final chunk carrying `finish_reason` and a
+/// terminal `data: [DONE]`.
+fn stream_chat_response(
+    state: AppState,
+    model: Arc<crate::state::LoadedModel>,
+    request: ChatRequest,
+    prompt: String,
+    batch_permit: tokio::sync::OwnedSemaphorePermit,
+    reservation: qwen3_inference::Reservation,
+) -> Sse<Pin<Box<dyn Stream<Item = Result<Event, Infallible>> + Send>>> {
+    let completion_tokens = Arc::new(AtomicU64::new(0));
+    let guard = StreamCompletionGuard {
+        state,
+        model_id: request.model.clone(),
+        completion_tokens: completion_tokens.clone(),
+        started_at: std::time::Instant::now(),
+    };
+
+    let max_tokens = request.max_tokens.unwrap_or(100);
+    let temperature = request.temperature.unwrap_or(0.7);
+    let top_p = request.top_p.unwrap_or(0.9);
+
+    let stream = async_stream::stream! {
+        // Keeps the generator (and its Drop-on-end `guard`, plus the
+        // admission control acquired in `admit_request`) alive for the
+        // whole stream, whether it runs to completion or is dropped early
+        // by a client disconnect.
+        let _guard = guard;
+        let _batch_permit = batch_permit;
+        let _reservation = reservation;
+
+        let mut transformer_guard = model.transformer.write().await;
+        let context_management = transformer_guard.config.inference_params.context_management.clone();
+        let transformer = transformer_guard.transformer_mut();
+
+        let tokenizer = match Tokenizer::new(
+            &model.info.path.to_string_lossy(),
+            transformer.config.vocab_size,
+            false,
+        ) {
+            Ok(tokenizer) => tokenizer,
+            Err(err) => {
+                yield Ok(Event::default().event("error").data(err.to_string()));
+                return;
+            }
+        };
+
+        let prompt_tokens = tokenizer.encode(&prompt);
+        if prompt_tokens.is_empty() {
+            yield Ok(Event::default().event("error").data("Empty prompt"));
+            return;
+        }
+
+        let mut sampler = Sampler::new(transformer.config.vocab_size, temperature, top_p, 42);
+
+        let seq_len = transformer.config.seq_len;
+        let sliding = sliding_window_params(&context_management);
+        let mut token = prompt_tokens[0];
+        let mut pos = 0;
+
+        for &next_token in &prompt_tokens[1..] {
+            if sliding.is_none() && pos >= seq_len {
+                break;
+            }
+            let _ = transformer.forward(token, cache_pos(pos, sliding));
+            token = next_token;
+            pos += 1;
+        }
+
+        yield Ok(sse_json_event(&ChatCompletionChunk {
+            object: "chat.completion.chunk",
+            choices: vec![ChatChunkChoice {
+                delta: ChatChunkDelta {
+                    role: Some("assistant".to_string()),
+                    content: None,
+                },
+                finish_reason: None,
+            }],
+        }));
+
+        let mut generated = 0usize;
+        let mut finish_reason = "stop";
+
+        while generated < max_tokens && (sliding.is_some() || pos < seq_len) {
+            let logits = transformer.forward(token, cache_pos(pos, sliding));
+            let mut logits_copy = logits.to_vec();
+            let next_token = sampler.sample(&mut logits_copy);
+
+            if next_token == tokenizer.eos_token_id as usize
+                || next_token == tokenizer.bos_token_id as usize
+            {
+                break;
+            }
+
+            let text = tokenizer.decode(next_token);
+            completion_tokens.fetch_add(1, Ordering::Relaxed);
+            generated += 1;
+            token = next_token;
+            pos += 1;
+
+            yield Ok(sse_json_event(&ChatCompletionChunk {
+                object: "chat.completion.chunk",
+                choices: vec![ChatChunkChoice {
+                    delta: ChatChunkDelta {
+                        role: None,
+                        content: Some(text),
+                    },
+                    finish_reason: None,
+                }],
+            }));
+
+            if generated >= max_tokens {
+                finish_reason = "length";
+            }
+        }
+
+        yield Ok(sse_json_event(&ChatCompletionChunk {
+            object: "chat.completion.chunk",
+            choices: vec![ChatChunkChoice {
+                delta: ChatChunkDelta::default(),
+                finish_reason: Some(finish_reason.to_string()),
+            }],
+        }));
+
+        yield Ok(Event::default().data("[DONE]"));
+    };
+
+    Sse::new(Box::pin(stream) as Pin<Box<dyn Stream<Item = Result<Event, Infallible>> + Send>>)
+}
+
+fn sse_json_event(chunk: &ChatCompletionChunk) -> Event {
+    Event::default().json_data(chunk).unwrap_or_else(|err| {
+        Event::default()
+            .event("error")
+            .data(format!("Failed to serialize chunk: {err}"))
+    })
+}
+
 fn generate_api_response(
     transformer: &mut qwen3_inference::transformer::Transformer,
     tokenizer: &qwen3_inference::tokenizer::Tokenizer,
     sampler: &mut qwen3_inference::sampler::Sampler,
     prompt: &str,
     max_tokens: usize,
+    context_management: &qwen3_inference::ContextManagement,
 ) -> anyhow::Result<String> {
     let prompt_tokens = tokenizer.encode(prompt);
     if prompt_tokens.is_empty() {
@@ -139,24 +577,25 @@ fn generate_api_response(
     }
 
     let seq_len = transformer.config.seq_len;
+    let sliding = sliding_window_params(context_management);
     let mut response_tokens = Vec::new();
     let mut token = prompt_tokens[0];
     let mut pos = 0;
 
     // Process prompt tokens first
     for &next_token in &prompt_tokens[1..] {
-        if pos >= seq_len {
+        if sliding.is_none() && pos >= seq_len {
             break;
         }
-        let _ = transformer.forward(token, pos);
+        let _ = transformer.forward(token, cache_pos(pos, sliding));
         token = next_token;
         pos += 1;
     }
 
     // Generate new tokens
     let mut generated_count = 0;
-    while generated_count < max_tokens && pos < seq_len {
-        let logits = transformer.forward(token, pos);
+    while generated_count < max_tokens && (sliding.is_some() || pos < seq_len) {
+        let logits = transformer.forward(token, cache_pos(pos, sliding));
         let mut logits_copy = logits.to_vec();
         let next_token = sampler.sample(&mut logits_copy);
 
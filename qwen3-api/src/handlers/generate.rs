@@ -1,7 +1,20 @@
+use crate::handlers::generation_support::{cache_pos, sliding_window_params};
 use crate::state::AppState;
-use axum::{extract::State, Json};
+use axum::{
+    extract::State,
+    response::{
+        sse::{Event, Sse},
+        IntoResponse, Response,
+    },
+    Json,
+};
+use futures_core::Stream;
 use qwen3_inference::{sampler::Sampler, tokenizer::Tokenizer};
 use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 
 #[derive(Debug, Deserialize)]
 pub struct GenerateRequest {
@@ -10,7 +23,7 @@ pub struct GenerateRequest {
     pub max_tokens: Option<usize>,
     pub temperature: Option<f32>,
     pub top_p: Option<f32>,
-    pub stop: Option<Vec<String>>,
+    pub stream: Option<bool>,
 }
 
 #[derive(Debug, Serialize)]
@@ -26,10 +39,76 @@ pub struct Usage {
     pub total_tokens: usize,
 }
 
+/// One streamed token: the decoded text plus its id, so clients that want
+/// to re-tokenize (or just log ids) don't need to re-run the tokenizer.
+#[derive(Debug, Serialize)]
+struct GenerateStreamToken {
+    delta: String,
+    token_id: usize,
+}
+
+/// Terminal SSE event carrying the same `Usage` accounting as the buffered
+/// response would have, plus how long generation took end to end.
+#[derive(Debug, Serialize)]
+struct GenerateStreamDone {
+    usage: Usage,
+    elapsed_ms: u128,
+}
+
+/// Response type for `generate_handler`: a buffered `GenerateResponse` when
+/// `stream` isn't set, or an SSE stream of per-token events when it is.
+pub enum GenerateHandlerResponse {
+    Buffered(Json<GenerateResponse>),
+    Streamed(Sse<Pin<Box<dyn Stream<Item = Result<Event, Infallible>> + Send>>>),
+}
+
+impl IntoResponse for GenerateHandlerResponse {
+    fn into_response(self) -> Response {
+        match self {
+            GenerateHandlerResponse::Buffered(json) => json.into_response(),
+            GenerateHandlerResponse::Streamed(sse) => sse.into_response(),
+        }
+    }
+}
+
+/// Decrements `active_requests` and folds whatever completion-token count
+/// was reached into the model's stats, no matter whether the stream ran to
+/// completion or the client disconnected mid-generation.
+struct StreamGenerationGuard {
+    state: AppState,
+    model_id: String,
+    completion_tokens: Arc<AtomicU64>,
+    started_at: std::time::Instant,
+}
+
+impl Drop for StreamGenerationGuard {
+    fn drop(&mut self) {
+        let completion_tokens = self.completion_tokens.load(Ordering::Relaxed);
+        if let Some(mut model) = self.state.models.get_mut(&self.model_id) {
+            model.request_count.fetch_add(1, Ordering::Relaxed);
+            model
+                .total_tokens_generated
+                .fetch_add(completion_tokens, Ordering::Relaxed);
+            model.last_inference_at = Some(std::time::Instant::now());
+        }
+        self.state.active_requests.fetch_sub(1, Ordering::Relaxed);
+
+        crate::metrics::REQUESTS_TOTAL
+            .with_label_values(&[&self.model_id])
+            .inc();
+        crate::metrics::TOKENS_GENERATED_TOTAL
+            .with_label_values(&[&self.model_id])
+            .inc_by(completion_tokens);
+        crate::metrics::GENERATION_LATENCY_SECONDS
+            .with_label_values(&[&self.model_id])
+            .observe(self.started_at.elapsed().as_secs_f64());
+    }
+}
+
 pub async fn generate_handler(
     State(state): State<AppState>,
     Json(request): Json<GenerateRequest>,
-) -> Result<Json<GenerateResponse>, (axum::http::StatusCode, String)> {
+) -> Result<GenerateHandlerResponse, (axum::http::StatusCode, String)> {
     // Increment active requests
     state
         .active_requests
@@ -43,7 +122,27 @@ pub async fn generate_handler(
         )
     })?;
 
-    // Update last used time
+    let (batch_permit, reservation) = match admit_request(&model, &request).await {
+        Ok(admission) => admission,
+        Err(err) => {
+            state
+                .active_requests
+                .fetch_sub(1, std::sync::atomic::Ordering::Relaxed);
+            return Err(err);
+        }
+    };
+
+    if request.stream == Some(true) {
+        return Ok(GenerateHandlerResponse::Streamed(stream_generate_response(
+            state,
+            model,
+            request,
+            batch_permit,
+            reservation,
+        )));
+    }
+
+    // Update last used time and generate
     {
         let mut transformer = model.transformer.write().await;
 
@@ -52,6 +151,8 @@ pub async fn generate_handler(
         let temperature = request.temperature.unwrap_or(0.7);
         let top_p = request.top_p.unwrap_or(0.9);
 
+        let context_management = transformer.config.inference_params.context_management.clone();
+
         // Get underlying transformer and tokenizer
         let transformer = transformer.transformer_mut();
         let tokenizer = Tokenizer::new(
@@ -68,6 +169,8 @@ pub async fn generate_handler(
             42, // seed
         );
 
+        let timer = crate::metrics::GenerationTimer::start(&request.model);
+
         // Generate response using API-friendly generation
         let response_text = generate_api_response(
             transformer,
@@ -75,12 +178,18 @@ pub async fn generate_handler(
             &mut sampler,
             &request.prompt,
             max_tokens,
+            &context_management,
         )
         .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
         // Count tokens
         let prompt_tokens = tokenizer.encode(&request.prompt).len();
         let completion_tokens = tokenizer.encode(&response_text).len();
+        timer.finish(completion_tokens as u64);
+        // Reservation/permit are estimate-only admission control; drop them
+        // once generation is complete rather than holding them past this block.
+        drop(reservation);
+        drop(batch_permit);
 
         let response = GenerateResponse {
             text: response_text.trim().to_string(),
@@ -106,16 +215,214 @@ pub async fn generate_handler(
         state
             .active_requests
             .fetch_sub(1, std::sync::atomic::Ordering::Relaxed);
-        Ok(Json(response))
+        Ok(GenerateHandlerResponse::Buffered(Json(response)))
     }
 }
 
+/// Admission control mirroring `chat::admit_request`: caps concurrent
+/// generations per model at `memory_limits.max_batch_size` and reserves
+/// this request's estimated KV-cache bytes against
+/// `memory_limits.max_memory_mb` before any tokens are generated.
+async fn admit_request(
+    model: &crate::state::LoadedModel,
+    request: &GenerateRequest,
+) -> Result<
+    (tokio::sync::OwnedSemaphorePermit, qwen3_inference::Reservation),
+    (axum::http::StatusCode, String),
+> {
+    let (memory_limits, n_layers, n_kv_heads, head_dim, quantization, vocab_size) = {
+        let transformer = model.transformer.read().await;
+        (
+            transformer.config.memory_limits,
+            transformer.config.base.n_layers,
+            transformer.config.base.n_kv_heads,
+            transformer.config.base.head_dim,
+            transformer.config.quantization,
+            transformer.config.base.vocab_size,
+        )
+    };
+
+    let batch_permit = model
+        .batch_semaphore
+        .clone()
+        .try_acquire_owned()
+        .map_err(|_| {
+            (
+                axum::http::StatusCode::SERVICE_UNAVAILABLE,
+                format!(
+                    "Model '{}' is at its concurrency limit (max_batch_size = {})",
+                    request.model, memory_limits.max_batch_size
+                ),
+            )
+        })?;
+
+    let tokenizer = Tokenizer::new(&model.info.path.to_string_lossy(), vocab_size, false)
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let prompt_token_count = tokenizer.encode(&request.prompt).len();
+    let max_tokens = request.max_tokens.unwrap_or(100);
+    let effective_seq_len =
+        (prompt_token_count + max_tokens).min(memory_limits.max_context_length);
+    let kv_cache_bytes = qwen3_inference::estimate_kv_cache_bytes(
+        n_layers,
+        n_kv_heads,
+        head_dim,
+        effective_seq_len,
+        quantization,
+    );
+
+    let reservation = model
+        .memory_limiter
+        .try_reserve(kv_cache_bytes)
+        .ok_or_else(|| memory_unavailable_response(&model.memory_limiter, kv_cache_bytes))?;
+
+    Ok((batch_permit, reservation))
+}
+
+fn memory_unavailable_response(
+    limiter: &qwen3_inference::MemoryLimiter,
+    requested_bytes: u64,
+) -> (axum::http::StatusCode, String) {
+    let requested_mib = requested_bytes as f64 / (1024.0 * 1024.0);
+    let available_mib = limiter
+        .capacity_bytes()
+        .saturating_sub(limiter.reserved_bytes()) as f64
+        / (1024.0 * 1024.0);
+    (
+        axum::http::StatusCode::SERVICE_UNAVAILABLE,
+        format!("requested {requested_mib:.1} MiB, {available_mib:.1} MiB available"),
+    )
+}
+
+/// Builds the SSE stream for `stream: true` requests: generates one token at
+/// a time, yielding each as soon as it's sampled, then a final `done` event
+/// carrying `Usage` and elapsed time.
+fn stream_generate_response(
+    state: AppState,
+    model: Arc<crate::state::LoadedModel>,
+    request: GenerateRequest,
+    batch_permit: tokio::sync::OwnedSemaphorePermit,
+    reservation: qwen3_inference::Reservation,
+) -> Sse<Pin<Box<dyn Stream<Item = Result<Event, Infallible>> + Send>>> {
+    let completion_tokens = Arc::new(AtomicU64::new(0));
+    let guard = StreamGenerationGuard {
+        state,
+        model_id: request.model.clone(),
+        completion_tokens: completion_tokens.clone(),
+        started_at: std::time::Instant::now(),
+    };
+
+    let max_tokens = request.max_tokens.unwrap_or(100);
+    let temperature = request.temperature.unwrap_or(0.7);
+    let top_p = request.top_p.unwrap_or(0.9);
+    let started_at = std::time::Instant::now();
+
+    let stream = async_stream::stream! {
+        // Keeps the generator (and its Drop-on-end `guard`, plus the
+        // admission control acquired in `admit_request`) alive for the
+        // whole stream, whether it runs to completion or is dropped early
+        // by a client disconnect.
+        let _guard = guard;
+        let _batch_permit = batch_permit;
+        let _reservation = reservation;
+
+        let mut transformer_guard = model.transformer.write().await;
+        let context_management = transformer_guard.config.inference_params.context_management.clone();
+        let transformer = transformer_guard.transformer_mut();
+
+        let tokenizer = match Tokenizer::new(
+            &model.info.path.to_string_lossy(),
+            transformer.config.vocab_size,
+            false,
+        ) {
+            Ok(tokenizer) => tokenizer,
+            Err(err) => {
+                yield Ok(Event::default().event("error").data(err.to_string()));
+                return;
+            }
+        };
+
+        let prompt_tokens = tokenizer.encode(&request.prompt);
+        if prompt_tokens.is_empty() {
+            yield Ok(Event::default().event("error").data("Empty prompt"));
+            return;
+        }
+        let prompt_token_count = prompt_tokens.len();
+
+        let mut sampler = Sampler::new(transformer.config.vocab_size, temperature, top_p, 42);
+
+        let seq_len = transformer.config.seq_len;
+        let sliding = sliding_window_params(&context_management);
+        let mut token = prompt_tokens[0];
+        let mut pos = 0;
+
+        for &next_token in &prompt_tokens[1..] {
+            if sliding.is_none() && pos >= seq_len {
+                break;
+            }
+            let _ = transformer.forward(token, cache_pos(pos, sliding));
+            token = next_token;
+            pos += 1;
+        }
+
+        let mut generated = 0usize;
+
+        while generated < max_tokens && (sliding.is_some() || pos < seq_len) {
+            let logits = transformer.forward(token, cache_pos(pos, sliding));
+            let mut logits_copy = logits.to_vec();
+            let next_token = sampler.sample(&mut logits_copy);
+
+            if next_token == tokenizer.eos_token_id as usize
+                || next_token == tokenizer.bos_token_id as usize
+            {
+                break;
+            }
+
+            let text = tokenizer.decode(next_token);
+            completion_tokens.fetch_add(1, Ordering::Relaxed);
+            generated += 1;
+            token = next_token;
+            pos += 1;
+
+            yield Ok(sse_json_event(&GenerateStreamToken {
+                delta: text,
+                token_id: next_token,
+            }));
+        }
+
+        let usage = Usage {
+            prompt_tokens: prompt_token_count,
+            completion_tokens: generated,
+            total_tokens: prompt_token_count + generated,
+        };
+
+        yield Ok(Event::default().event("done").json_data(GenerateStreamDone {
+            usage,
+            elapsed_ms: started_at.elapsed().as_millis(),
+        }).unwrap_or_else(|err| {
+            Event::default()
+                .event("error")
+                .data(format!("Failed to serialize done event: {err}"))
+        }));
+    };
+
+    Sse::new(Box::pin(stream) as Pin<Box<dyn Stream<Item = Result<Event, Infallible>> + Send>>)
+}
+
+fn sse_json_event(token: &GenerateStreamToken) -> Event {
+    Event::default().json_data(token).unwrap_or_else(|err| {
+        Event::default()
+            .event("error")
+            .data(format!("Failed to serialize token: {err}"))
+    })
+}
+
 fn generate_api_response(
     transformer: &mut qwen3_inference::transformer::Transformer,
     tokenizer: &qwen3_inference::tokenizer::Tokenizer,
     sampler: &mut qwen3_inference::sampler::Sampler,
     prompt: &str,
     max_tokens: usize,
+    context_management: &qwen3_inference::ContextManagement,
 ) -> anyhow::Result<String> {
     let prompt_tokens = tokenizer.encode(prompt);
     if prompt_tokens.is_empty() {
@@ -123,24 +430,25 @@ fn generate_api_response(
     }
 
     let seq_len = transformer.config.seq_len;
+    let sliding = sliding_window_params(context_management);
     let mut response_tokens = Vec::new();
     let mut token = prompt_tokens[0];
     let mut pos = 0;
 
     // Process prompt tokens first
     for &next_token in &prompt_tokens[1..] {
-        if pos >= seq_len {
+        if sliding.is_none() && pos >= seq_len {
             break;
         }
-        let _ = transformer.forward(token, pos);
+        let _ = transformer.forward(token, cache_pos(pos, sliding));
         token = next_token;
         pos += 1;
     }
 
     // Generate new tokens
     let mut generated_count = 0;
-    while generated_count < max_tokens && pos < seq_len {
-        let logits = transformer.forward(token, pos);
+    while generated_count < max_tokens && (sliding.is_some() || pos < seq_len) {
+        let logits = transformer.forward(token, cache_pos(pos, sliding));
         let mut logits_copy = logits.to_vec();
         let next_token = sampler.sample(&mut logits_copy);
 
@@ -0,0 +1,36 @@
+//! Sliding-window/attention-sink helpers shared by every handler that runs
+//! its own per-token generation loop (`chat`, `generate`), so each one
+//! honors `ContextManagement::Sliding` the same way instead of re-deriving
+//! this logic (or silently dropping it) per handler.
+
+/// Extracts `(window_size, sink_size)` from the active
+/// [`qwen3_inference::ContextManagement`] strategy, or `None` when it isn't
+/// `Sliding` and generation should keep using the model's absolute,
+/// bounded-at-`seq_len` positions as before.
+pub(crate) fn sliding_window_params(
+    context_management: &qwen3_inference::ContextManagement,
+) -> Option<(usize, usize)> {
+    match context_management {
+        qwen3_inference::ContextManagement::Sliding {
+            window_size,
+            sink_size,
+        } => Some((*window_size, *sink_size)),
+        _ => None,
+    }
+}
+
+/// Maps an absolute generation position to the KV-cache slot
+/// `transformer.forward` should write to. The first `sink_size` positions
+/// are pinned permanently (the attention sinks that keep softmax stable at
+/// long context); every position after that wraps into the `window_size`
+/// slots behind them, evicting the oldest non-sink entry. RoPE then sees a
+/// contiguous relative position rather than the unbounded absolute one.
+/// Passing `sliding: None` (non-`Sliding` strategies) is the identity map.
+pub(crate) fn cache_pos(pos: usize, sliding: Option<(usize, usize)>) -> usize {
+    match sliding {
+        Some((window_size, sink_size)) if pos >= sink_size => {
+            sink_size + (pos - sink_size) % window_size
+        }
+        _ => pos,
+    }
+}
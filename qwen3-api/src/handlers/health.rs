@@ -19,6 +19,10 @@ pub struct MemoryUsage {
     pub active_requests: usize,
     pub loaded_models: usize,
     pub total_memory_mb: Option<u64>,
+    pub process_rss_mb: u64,
+    pub available_memory_mb: u64,
+    pub cpu_usage_percent: f32,
+    pub models_estimated_memory_mb: u64,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -32,46 +36,34 @@ pub async fn health_check(
 ) -> Json<HealthResponse> {
     let active_requests = state.active_requests.load(std::sync::atomic::Ordering::Relaxed);
     let loaded_models = state.list_models();
-    
+    let snapshot = state.resource_monitor.snapshot(&loaded_models);
+
+    let status = if snapshot.memory_pressure() > state.config.resource_budget.memory_pressure_threshold {
+        "degraded"
+    } else {
+        "healthy"
+    };
+
     let memory_usage = MemoryUsage {
         active_requests,
         loaded_models: loaded_models.len(),
-        total_memory_mb: get_memory_usage(),
+        total_memory_mb: Some(snapshot.total_memory_mb),
+        process_rss_mb: snapshot.process_rss_mb,
+        available_memory_mb: snapshot.available_memory_mb,
+        cpu_usage_percent: snapshot.cpu_usage_percent,
+        models_estimated_memory_mb: snapshot.models_estimated_memory_mb,
     };
-    
+
     let models = ModelsInfo {
         count: loaded_models.len(),
         loaded: loaded_models.into_iter().map(|m| m.id).collect(),
     };
-    
+
     Json(HealthResponse {
-        status: "healthy".to_string(),
+        status: status.to_string(),
         timestamp: chrono::Utc::now().to_rfc3339(),
         version: env!("CARGO_PKG_VERSION").to_string(),
         memory_usage,
         models,
     })
 }
-
-#[cfg(target_os = "linux")]
-fn get_memory_usage() -> Option<u64> {
-    use std::fs;
-    
-    if let Ok(status) = fs::read_to_string("/proc/self/status") {
-        for line in status.lines() {
-            if line.starts_with("VmRSS:") {
-                if let Some(kb_str) = line.split_whitespace().nth(1) {
-                    if let Ok(kb) = kb_str.parse::<u64>() {
-                        return Some(kb / 1024); // Convert to MB
-                    }
-                }
-            }
-        }
-    }
-    None
-}
-
-#[cfg(not(target_os = "linux"))]
-fn get_memory_usage() -> Option<u64> {
-    None
-}
\ No newline at end of file
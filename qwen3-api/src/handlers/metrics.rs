@@ -0,0 +1,23 @@
+use axum::{
+    extract::State,
+    http::{header, HeaderMap, HeaderValue},
+};
+
+use crate::state::AppState;
+
+/// `GET /metrics`: Prometheus text exposition of request/token counters,
+/// loaded-model and active-request gauges, and generation-latency
+/// histograms — the same numbers `/health`/`/status` report, in a format
+/// standard scrapers (Prometheus, Grafana Agent, OTel Collector) read
+/// natively without custom glue.
+pub async fn metrics_handler(State(state): State<AppState>) -> (HeaderMap, String) {
+    crate::metrics::sync_gauges(&state);
+
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("text/plain; version=0.0.4"),
+    );
+
+    (headers, crate::metrics::render())
+}
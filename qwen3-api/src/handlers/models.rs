@@ -1,3 +1,4 @@
+use crate::error::ApiError;
 use crate::state::{AppState, ModelInfo};
 use axum::{
     extract::{Path, State},
@@ -41,46 +42,34 @@ pub async fn load_model(
     Path(model_id): Path<String>,
     State(state): State<AppState>,
     Json(request): Json<LoadModelRequest>,
-) -> Json<LoadModelResponse> {
-    // Enforce model limits
-    if let Err(e) = state.enforce_model_limits() {
-        return Json(LoadModelResponse {
-            success: false,
-            model_id: model_id.clone(),
-            message: format!("Failed to enforce model limits: {e}"),
-        });
-    }
+) -> Result<Json<LoadModelResponse>, ApiError> {
+    // Enforce model limits, evicting LRU models to make room for this one
+    let incoming_size = std::path::PathBuf::from(&state.config.models.directory)
+        .join(format!("{model_id}.bin"))
+        .metadata()
+        .map(|m| m.len())
+        .unwrap_or(0);
+    state.enforce_model_limits(incoming_size)?;
 
-    // Load the model
-    match state
+    let model_id = state
         .load_model(&model_id, request.quantization.as_deref())
-        .await
-    {
-        Ok(model_id) => Json(LoadModelResponse {
-            success: true,
-            model_id,
-            message: "Model loaded successfully".to_string(),
-        }),
-        Err(e) => Json(LoadModelResponse {
-            success: false,
-            model_id,
-            message: format!("Failed to load model: {e}"),
-        }),
-    }
+        .await?;
+
+    Ok(Json(LoadModelResponse {
+        success: true,
+        model_id,
+        message: "Model loaded successfully".to_string(),
+    }))
 }
 
 pub async fn unload_model(
     Path(model_id): Path<String>,
     State(state): State<AppState>,
-) -> Json<UnloadModelResponse> {
-    match state.unload_model(&model_id) {
-        Ok(_) => Json(UnloadModelResponse {
-            success: true,
-            message: format!("Model {model_id} unloaded successfully"),
-        }),
-        Err(e) => Json(UnloadModelResponse {
-            success: false,
-            message: format!("Failed to unload model: {e}"),
-        }),
-    }
+) -> Result<Json<UnloadModelResponse>, ApiError> {
+    state.unload_model(&model_id)?;
+
+    Ok(Json(UnloadModelResponse {
+        success: true,
+        message: format!("Model {model_id} unloaded successfully"),
+    }))
 }
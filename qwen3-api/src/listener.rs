@@ -0,0 +1,177 @@
+use anyhow::Result;
+use std::io;
+use std::path::Path;
+use tokio::net::{TcpListener, UnixListener};
+
+use crate::config::ServerConfig;
+
+/// Either side of an accepted connection, so callers that only care about
+/// `AsyncRead + AsyncWrite` (i.e. `axum::serve`) don't need to know whether
+/// it came in over TCP or a Unix domain socket.
+pub enum Connection {
+    Tcp(tokio::net::TcpStream),
+    Unix(tokio::net::UnixStream),
+}
+
+impl tokio::io::AsyncRead for Connection {
+    fn poll_read(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &mut tokio::io::ReadBuf<'_>,
+    ) -> std::task::Poll<io::Result<()>> {
+        match self.get_mut() {
+            Connection::Tcp(s) => std::pin::Pin::new(s).poll_read(cx, buf),
+            Connection::Unix(s) => std::pin::Pin::new(s).poll_read(cx, buf),
+        }
+    }
+}
+
+impl tokio::io::AsyncWrite for Connection {
+    fn poll_write(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &[u8],
+    ) -> std::task::Poll<io::Result<usize>> {
+        match self.get_mut() {
+            Connection::Tcp(s) => std::pin::Pin::new(s).poll_write(cx, buf),
+            Connection::Unix(s) => std::pin::Pin::new(s).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_flush(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<io::Result<()>> {
+        match self.get_mut() {
+            Connection::Tcp(s) => std::pin::Pin::new(s).poll_flush(cx),
+            Connection::Unix(s) => std::pin::Pin::new(s).poll_flush(cx),
+        }
+    }
+
+    fn poll_shutdown(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<io::Result<()>> {
+        match self.get_mut() {
+            Connection::Tcp(s) => std::pin::Pin::new(s).poll_shutdown(cx),
+            Connection::Unix(s) => std::pin::Pin::new(s).poll_shutdown(cx),
+        }
+    }
+}
+
+/// Address of an accepted [`Connection`], mirroring the connection itself —
+/// `axum::serve::Listener::Addr` just needs to be `Debug + Clone + Send +
+/// Sync`, so the Unix side doesn't need anything beyond the socket path.
+#[derive(Debug, Clone)]
+pub enum ConnectionAddr {
+    Tcp(std::net::SocketAddr),
+    Unix(Option<std::path::PathBuf>),
+}
+
+/// Binds either a TCP or a Unix domain socket depending on `ServerConfig`,
+/// so the rest of the server only ever deals in `AsyncRead + AsyncWrite`
+/// connections. A `bind_address` of the form `unix:/path/to/socket.sock`
+/// selects the Unix path; anything else is parsed as an IP and bound over
+/// TCP with `TCP_NODELAY` as before.
+pub enum Listener {
+    Tcp(TcpListener),
+    Unix {
+        listener: UnixListener,
+        path: std::path::PathBuf,
+        /// Whether the socket file should be removed on shutdown. Mirrors
+        /// `ServerConfig::reuse`, which also governs removing a stale file
+        /// left behind by a previous, uncleanly-terminated run before bind.
+        cleanup_on_drop: bool,
+    },
+}
+
+impl Listener {
+    /// Binds according to `config.bind_address`: a `unix:`-prefixed value
+    /// binds a Unix domain socket at the given path (removing a stale
+    /// socket file first when `config.reuse` is set), anything else is
+    /// parsed as an IP and bound over TCP on `config.port`.
+    pub fn bind(config: &ServerConfig) -> Result<Self> {
+        match config.bind_address.strip_prefix("unix:") {
+            Some(path) => {
+                let path = Path::new(path).to_path_buf();
+                if config.reuse && path.exists() {
+                    std::fs::remove_file(&path)?;
+                }
+                let listener = UnixListener::bind(&path)?;
+                Ok(Listener::Unix {
+                    listener,
+                    path,
+                    cleanup_on_drop: config.reuse,
+                })
+            }
+            None => {
+                let addr = std::net::SocketAddr::new(config.bind_address.parse()?, config.port);
+                Ok(Listener::Tcp(bind_with_nodelay(addr)?))
+            }
+        }
+    }
+}
+
+impl Drop for Listener {
+    fn drop(&mut self) {
+        if let Listener::Unix { path, cleanup_on_drop: true, .. } = self {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+impl axum::serve::Listener for Listener {
+    type Io = Connection;
+    type Addr = ConnectionAddr;
+
+    async fn accept(&mut self) -> (Self::Io, Self::Addr) {
+        loop {
+            let accepted = match self {
+                Listener::Tcp(listener) => listener
+                    .accept()
+                    .await
+                    .map(|(stream, addr)| (Connection::Tcp(stream), ConnectionAddr::Tcp(addr))),
+                Listener::Unix { listener, .. } => listener.accept().await.map(|(stream, addr)| {
+                    (Connection::Unix(stream), ConnectionAddr::Unix(addr.as_pathname().map(Path::to_path_buf)))
+                }),
+            };
+
+            match accepted {
+                Ok(accepted) => return accepted,
+                // `axum::serve`'s own `Listener for TcpListener` impl also
+                // just logs and retries on accept errors rather than
+                // tearing down the whole server over one bad connection.
+                Err(err) => tracing::debug!("Failed to accept connection: {err}"),
+            }
+        }
+    }
+
+    fn local_addr(&self) -> io::Result<Self::Addr> {
+        match self {
+            Listener::Tcp(listener) => listener.local_addr().map(ConnectionAddr::Tcp),
+            Listener::Unix { listener, .. } => listener
+                .local_addr()
+                .map(|addr| ConnectionAddr::Unix(addr.as_pathname().map(Path::to_path_buf))),
+        }
+    }
+}
+
+/// Binds the listening socket with `TCP_NODELAY` enabled so streamed tokens
+/// aren't held back by Nagle's algorithm waiting to coalesce with the next
+/// write — important for chat/generate responses that flush one token at a
+/// time. `tokio::net::TcpListener::bind` doesn't expose socket options, so
+/// the socket is built with `socket2` and handed off to tokio afterwards.
+fn bind_with_nodelay(addr: std::net::SocketAddr) -> Result<TcpListener> {
+    use socket2::{Domain, Socket, Type};
+
+    let domain = if addr.is_ipv6() { Domain::IPV6 } else { Domain::IPV4 };
+    let socket = Socket::new(domain, Type::STREAM, None)?;
+    socket.set_nodelay(true)?;
+    socket.set_reuse_address(true)?;
+    socket.bind(&addr.into())?;
+    socket.listen(1024)?;
+    socket.set_nonblocking(true)?;
+
+    let std_listener: std::net::TcpListener = socket.into();
+    Ok(TcpListener::from_std(std_listener)?)
+}
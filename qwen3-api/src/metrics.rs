@@ -0,0 +1,127 @@
+//! Observability subsystem: Prometheus-format metrics derived from the
+//! per-model counters `AppState` already tracks (`request_count`,
+//! `total_tokens_generated`, `loaded_at`, `last_inference_at`), plus an
+//! optional OTLP push exporter for monitoring stacks that don't scrape.
+
+use once_cell::sync::Lazy;
+use prometheus::{
+    register_histogram_vec, register_int_counter_vec, register_int_gauge, Encoder, HistogramVec,
+    IntCounterVec, IntGauge, TextEncoder,
+};
+
+pub static REQUESTS_TOTAL: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "qwen3_requests_total",
+        "Total inference requests served, labeled by model_id",
+        &["model_id"]
+    )
+    .expect("qwen3_requests_total metric registration")
+});
+
+pub static TOKENS_GENERATED_TOTAL: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "qwen3_tokens_generated_total",
+        "Total tokens generated, labeled by model_id",
+        &["model_id"]
+    )
+    .expect("qwen3_tokens_generated_total metric registration")
+});
+
+pub static LOADED_MODELS: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!("qwen3_loaded_models", "Number of models currently loaded")
+        .expect("qwen3_loaded_models metric registration")
+});
+
+pub static ACTIVE_REQUESTS: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!("qwen3_active_requests", "In-flight inference requests")
+        .expect("qwen3_active_requests metric registration")
+});
+
+pub static GENERATION_LATENCY_SECONDS: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "qwen3_generation_latency_seconds",
+        "End-to-end generate() latency, labeled by model_id",
+        &["model_id"]
+    )
+    .expect("qwen3_generation_latency_seconds metric registration")
+});
+
+/// Times one `generate`/`chat` call end to end and, on [`finish`](Self::finish),
+/// bumps the request/token counters and records the elapsed time into
+/// `GENERATION_LATENCY_SECONDS` — all three labeled by `model_id` so a
+/// single dashboard panel can break throughput and latency out per model.
+pub struct GenerationTimer {
+    model_id: String,
+    started_at: std::time::Instant,
+}
+
+impl GenerationTimer {
+    pub fn start(model_id: &str) -> Self {
+        Self {
+            model_id: model_id.to_string(),
+            started_at: std::time::Instant::now(),
+        }
+    }
+
+    pub fn finish(self, completion_tokens: u64) {
+        REQUESTS_TOTAL.with_label_values(&[&self.model_id]).inc();
+        TOKENS_GENERATED_TOTAL
+            .with_label_values(&[&self.model_id])
+            .inc_by(completion_tokens);
+        GENERATION_LATENCY_SECONDS
+            .with_label_values(&[&self.model_id])
+            .observe(self.started_at.elapsed().as_secs_f64());
+    }
+}
+
+/// Refreshes the loaded-models and active-requests gauges from `AppState`
+/// just before rendering, so `/metrics` never drifts from what `/health`
+/// reports (both read the same live state, just in different formats).
+pub fn sync_gauges(state: &crate::state::AppState) {
+    LOADED_MODELS.set(state.models.len() as i64);
+    ACTIVE_REQUESTS.set(
+        state
+            .active_requests
+            .load(std::sync::atomic::Ordering::Relaxed) as i64,
+    );
+}
+
+/// Renders every registered metric in Prometheus text exposition format.
+pub fn render() -> String {
+    let metric_families = prometheus::gather();
+    let mut buffer = Vec::new();
+    TextEncoder::new()
+        .encode(&metric_families, &mut buffer)
+        .expect("prometheus text encoding");
+    String::from_utf8(buffer).expect("prometheus text output is valid utf-8")
+}
+
+/// Initializes the optional OTLP push exporter from `[telemetry]` config.
+/// No-op (metrics stay Prometheus-pull-only via `/metrics`) when
+/// `telemetry.otlp_endpoint` isn't set, so deployments that just scrape
+/// don't pay for a collector connection they never use.
+pub fn init_otlp_exporter(telemetry: &crate::config::TelemetryConfig) -> anyhow::Result<()> {
+    let Some(endpoint) = telemetry.otlp_endpoint.as_deref() else {
+        return Ok(());
+    };
+
+    use opentelemetry::KeyValue;
+    use opentelemetry_otlp::WithExportConfig;
+
+    let provider = opentelemetry_otlp::new_pipeline()
+        .metrics(opentelemetry_sdk::runtime::Tokio)
+        .with_exporter(
+            opentelemetry_otlp::new_exporter()
+                .tonic()
+                .with_endpoint(endpoint),
+        )
+        .with_resource(opentelemetry_sdk::Resource::new(vec![KeyValue::new(
+            "service.name",
+            telemetry.service_name.clone(),
+        )]))
+        .build()?;
+
+    opentelemetry::global::set_meter_provider(provider);
+    tracing::info!("OTLP metrics exporter pushing to {endpoint}");
+    Ok(())
+}
@@ -4,48 +4,143 @@ use axum::{
     middleware::Next,
     response::Response,
 };
-use std::time::Instant;
-use tracing::{info, warn};
+use once_cell::sync::Lazy;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tracing::{debug, info, warn};
+
+/// How often the rolling throughput summary is emitted. Per-request lines
+/// stay on every request; this just adds an aggregated line on top so
+/// sustained streaming workloads are observable without scrolling through
+/// per-token noise.
+const THROUGHPUT_LOG_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Request id that `logging_middleware` stores in each request's
+/// extensions. No handler reads it back, and it is not passed to the
+/// inference layer: profiler records (see `qwen3_inference::profiling`)
+/// carry no request id.
+#[derive(Debug, Clone)]
+pub struct RequestId(pub String);
+
+impl std::fmt::Display for RequestId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Rolling window of completed-request latencies, flushed into an
+/// aggregated log line on `THROUGHPUT_LOG_INTERVAL`.
+struct ThroughputWindow {
+    window_start: Instant,
+    latencies_us: Vec<u64>,
+}
+
+impl ThroughputWindow {
+    fn new() -> Self {
+        Self {
+            window_start: Instant::now(),
+            latencies_us: Vec::new(),
+        }
+    }
+}
+
+static THROUGHPUT_WINDOW: Lazy<Mutex<ThroughputWindow>> =
+    Lazy::new(|| Mutex::new(ThroughputWindow::new()));
+
+/// Records one completed request's latency and, if the current window has
+/// run its course, emits a rolling-metrics log line (requests/sec, average
+/// and p95 latency) and starts a fresh window.
+fn record_and_maybe_flush(duration: Duration) {
+    let mut window = THROUGHPUT_WINDOW.lock().unwrap();
+    window.latencies_us.push(duration.as_micros() as u64);
+
+    let elapsed = window.window_start.elapsed();
+    if elapsed < THROUGHPUT_LOG_INTERVAL {
+        return;
+    }
+
+    let mut latencies = std::mem::take(&mut window.latencies_us);
+    window.window_start = Instant::now();
+    drop(window);
+
+    if latencies.is_empty() {
+        return;
+    }
+
+    latencies.sort_unstable();
+    let count = latencies.len();
+    let sum_us: u64 = latencies.iter().sum();
+    let avg_ms = (sum_us as f64 / count as f64) / 1000.0;
+    let p95_index = ((count as f64 * 0.95).ceil() as usize).saturating_sub(1).min(count - 1);
+    let p95_ms = latencies[p95_index] as f64 / 1000.0;
+    let requests_per_sec = count as f64 / elapsed.as_secs_f64();
+
+    info!(
+        window_secs = elapsed.as_secs_f64(),
+        requests = count,
+        requests_per_sec = %format!("{requests_per_sec:.2}"),
+        avg_latency_ms = %format!("{avg_ms:.2}"),
+        p95_latency_ms = %format!("{p95_ms:.2}"),
+        "Rolling throughput summary"
+    );
+}
+
+fn micros_since_epoch() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_micros()
+}
 
 pub async fn logging_middleware(
     method: Method,
     uri: Uri,
     headers: HeaderMap,
-    request: Request,
+    mut request: Request,
     next: Next,
 ) -> Response {
     let start = Instant::now();
+    let start_us = micros_since_epoch();
     let request_id = generate_request_id();
 
-    info!(
+    request.extensions_mut().insert(RequestId(request_id.clone()));
+
+    debug!(
         request_id = %request_id,
         method = %method,
         uri = %uri,
         user_agent = ?headers.get("user-agent"),
+        start_us = start_us,
         "Incoming request"
     );
 
-    let response = next.run(request).await;
+    let mut response = next.run(request).await;
     let duration = start.elapsed();
 
+    response
+        .headers_mut()
+        .insert("x-request-id", request_id.parse().unwrap());
+
     let status = response.status();
 
     if status.is_server_error() {
         warn!(
             request_id = %request_id,
             status = %status,
-            duration_ms = duration.as_millis(),
+            duration_us = duration.as_micros(),
             "Request completed with error"
         );
     } else {
-        info!(
+        debug!(
             request_id = %request_id,
             status = %status,
-            duration_ms = duration.as_millis(),
+            duration_us = duration.as_micros(),
             "Request completed"
         );
     }
 
+    record_and_maybe_flush(duration);
+
     response
 }
 
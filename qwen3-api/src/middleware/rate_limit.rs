@@ -1,8 +1,8 @@
 use axum::{
     extract::{Request, State},
-    http::{HeaderMap, StatusCode},
+    http::{HeaderMap, HeaderValue, StatusCode},
     middleware::Next,
-    response::Response,
+    response::{IntoResponse, Response},
 };
 use std::sync::Arc;
 use tokio::sync::Mutex;
@@ -21,42 +21,122 @@ impl RateLimitLayer {
     }
 }
 
+/// Shapes how a client's token bucket fills and drains: how much of the
+/// per-minute budget it may hold as an instantaneous burst, and how much
+/// extra slack is folded into the nominal 60s refill window.
+#[derive(Debug, Clone, Copy)]
+pub struct RateLimiterProfile {
+    pub burst_pct: f64,
+    pub duration_overhead: Duration,
+}
+
+impl RateLimiterProfile {
+    /// Lets a client spend almost its entire budget in one burst, then
+    /// wait out the rest of the window before refilling further.
+    #[allow(dead_code)]
+    pub fn burst() -> Self {
+        Self {
+            burst_pct: 0.99,
+            duration_overhead: Duration::from_millis(990),
+        }
+    }
+
+    /// Paces requests evenly across the window rather than allowing a
+    /// burst to drain the bucket up front.
+    #[allow(dead_code)]
+    pub fn throughput() -> Self {
+        Self {
+            burst_pct: 0.47,
+            duration_overhead: Duration::from_millis(10),
+        }
+    }
+}
+
+impl Default for RateLimiterProfile {
+    fn default() -> Self {
+        Self::throughput()
+    }
+}
+
+/// Per-client token bucket. `tokens` is fractional so slow trickles of
+/// traffic still accumulate refill between requests instead of rounding
+/// down to zero.
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(capacity: f64) -> Self {
+        Self {
+            tokens: capacity,
+            last_refill: Instant::now(),
+        }
+    }
+
+    fn refill(&mut self, rate_per_sec: f64, capacity: f64) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * rate_per_sec).min(capacity);
+        self.last_refill = now;
+    }
+}
+
+/// Time-to-next-token info surfaced to the client on rejection.
+#[allow(dead_code)]
+struct RateLimitRejection {
+    retry_after: Duration,
+    reset: Duration,
+}
+
 #[derive(Clone)]
 pub struct RateLimitState {
-    requests: Arc<Mutex<HashMap<String, Vec<Instant>>>>,
+    buckets: Arc<Mutex<HashMap<String, TokenBucket>>>,
     requests_per_minute: u64,
+    profile: RateLimiterProfile,
 }
 
 impl RateLimitState {
     #[allow(dead_code)]
-    fn new(requests_per_minute: u64) -> Self {
+    fn new(requests_per_minute: u64, profile: RateLimiterProfile) -> Self {
         Self {
-            requests: Arc::new(Mutex::new(HashMap::new())),
+            buckets: Arc::new(Mutex::new(HashMap::new())),
             requests_per_minute,
+            profile,
         }
     }
 
-    async fn check_rate_limit(&self, client_ip: &str
-    ) -> Result<(), StatusCode> {
-        let mut requests = self.requests.lock().await;
-        let now = Instant::now();
-        let window = Duration::from_secs(60);
-        
-        let client_requests = requests
+    fn capacity(&self) -> f64 {
+        self.requests_per_minute as f64 * self.profile.burst_pct
+    }
+
+    fn rate_per_sec(&self) -> f64 {
+        let window = Duration::from_secs(60) + self.profile.duration_overhead;
+        self.requests_per_minute as f64 / window.as_secs_f64()
+    }
+
+    async fn check_rate_limit(&self, client_ip: &str) -> Result<u64, RateLimitRejection> {
+        let mut buckets = self.buckets.lock().await;
+        let capacity = self.capacity();
+        let rate = self.rate_per_sec();
+
+        let bucket = buckets
             .entry(client_ip.to_string())
-            .or_insert_with(Vec::new);
-        
-        // Remove old requests
-        client_requests.retain(|&time| now.duration_since(time) < window);
-        
-        // Check if limit exceeded
-        if client_requests.len() >= self.requests_per_minute as usize {
-            return Err(StatusCode::TOO_MANY_REQUESTS);
+            .or_insert_with(|| TokenBucket::new(capacity));
+
+        bucket.refill(rate, capacity);
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(bucket.tokens.floor().max(0.0) as u64)
+        } else {
+            let tokens_needed = 1.0 - bucket.tokens;
+            let reset_needed = (capacity - bucket.tokens).max(0.0);
+            Err(RateLimitRejection {
+                retry_after: Duration::from_secs_f64(tokens_needed / rate),
+                reset: Duration::from_secs_f64(reset_needed / rate),
+            })
         }
-        
-        // Add current request
-        client_requests.push(now);
-        Ok(())
     }
 }
 
@@ -65,13 +145,34 @@ pub async fn rate_limit_middleware(
     headers: HeaderMap,
     request: Request,
     next: Next,
-) -> Result<Response, StatusCode> {
-    // Get client IP from headers or use a default
-    let client_ip = extract_client_ip(&headers).unwrap_or("unknown".to_string());
-    
-    state.check_rate_limit(&client_ip).await?;
-    
-    Ok(next.run(request).await)
+) -> Response {
+    let client_ip = extract_client_ip(&headers).unwrap_or_else(|| "unknown".to_string());
+
+    match state.check_rate_limit(&client_ip).await {
+        Ok(remaining) => {
+            let mut response = next.run(request).await;
+            if let Ok(value) = HeaderValue::from_str(&remaining.to_string()) {
+                response
+                    .headers_mut()
+                    .insert("X-RateLimit-Remaining", value);
+            }
+            response
+        }
+        Err(rejection) => rate_limited_response(&rejection),
+    }
+}
+
+fn rate_limited_response(rejection: &RateLimitRejection) -> Response {
+    let mut response = (StatusCode::TOO_MANY_REQUESTS, "Too Many Requests").into_response();
+    let headers = response.headers_mut();
+
+    if let Ok(value) = HeaderValue::from_str(&rejection.retry_after.as_secs().to_string()) {
+        headers.insert("Retry-After", value.clone());
+        headers.insert("X-RateLimit-Reset", value);
+    }
+    headers.insert("X-RateLimit-Remaining", HeaderValue::from_static("0"));
+
+    response
 }
 
 fn extract_client_ip(headers: &HeaderMap) -> Option<String> {
@@ -81,13 +182,13 @@ fn extract_client_ip(headers: &HeaderMap) -> Option<String> {
             return Some(ip.split(',').next()?.trim().to_string());
         }
     }
-    
+
     // Try X-Real-IP
     if let Some(real_ip) = headers.get("X-Real-IP") {
         if let Ok(ip) = real_ip.to_str() {
             return Some(ip.to_string());
         }
     }
-    
+
     None
-}
\ No newline at end of file
+}
@@ -0,0 +1,83 @@
+//! Cross-platform system resource reporting for the `/api/v1/health`
+//! endpoint. Replaces the old Linux-only `/proc/self/status` parsing (which
+//! left `MemoryUsage` blind on macOS/Windows) with a `sysinfo`-backed
+//! monitor that's refreshed once per `/health` call.
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use sysinfo::{Pid, System};
+
+/// Point-in-time resource reading handed to the health handler.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceSnapshot {
+    pub process_rss_mb: u64,
+    pub total_memory_mb: u64,
+    pub available_memory_mb: u64,
+    pub cpu_usage_percent: f32,
+    /// Sum of `ModelInfo.size` across every currently loaded model, as a
+    /// cheap stand-in for actual RSS attributable to model weights.
+    pub models_estimated_memory_mb: u64,
+}
+
+impl ResourceSnapshot {
+    /// Fraction of total system memory currently in use, `0.0` if total
+    /// memory couldn't be determined.
+    pub fn memory_pressure(&self) -> f32 {
+        if self.total_memory_mb == 0 {
+            return 0.0;
+        }
+        1.0 - (self.available_memory_mb as f32 / self.total_memory_mb as f32)
+    }
+}
+
+/// Wraps a `sysinfo::System`, refreshed on each `snapshot` call rather than
+/// on a background timer since `/health` isn't hit often enough for the
+/// refresh cost to matter.
+pub struct ResourceMonitor {
+    system: Mutex<System>,
+}
+
+impl std::fmt::Debug for ResourceMonitor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ResourceMonitor").finish_non_exhaustive()
+    }
+}
+
+impl ResourceMonitor {
+    pub fn new() -> Self {
+        Self {
+            system: Mutex::new(System::new_all()),
+        }
+    }
+
+    pub fn snapshot(&self, loaded_models: &[crate::state::ModelInfo]) -> ResourceSnapshot {
+        let mut system = self.system.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        system.refresh_memory();
+        system.refresh_cpu_usage();
+
+        let pid = Pid::from_u32(std::process::id());
+        system.refresh_processes(sysinfo::ProcessesToUpdate::Some(&[pid]), true);
+
+        let process_rss_mb = system
+            .process(pid)
+            .map(|process| process.memory() / 1024 / 1024)
+            .unwrap_or(0);
+
+        let models_estimated_memory_mb =
+            loaded_models.iter().map(|m| m.size).sum::<u64>() / 1024 / 1024;
+
+        ResourceSnapshot {
+            process_rss_mb,
+            total_memory_mb: system.total_memory() / 1024 / 1024,
+            available_memory_mb: system.available_memory() / 1024 / 1024,
+            cpu_usage_percent: system.global_cpu_usage(),
+            models_estimated_memory_mb,
+        }
+    }
+}
+
+impl Default for ResourceMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -3,16 +3,16 @@ use axum::{
     routing::get,
     Router,
 };
-use std::net::SocketAddr;
 use tower_http::{
+    compression::{predicate::SizeAbove, CompressionLayer},
     cors::CorsLayer,
     trace::TraceLayer,
     limit::RequestBodyLimitLayer,
 };
 use tracing::info;
 
-use crate::{config::Config, state::AppState};
-use crate::handlers::{chat, generate, models, health, openai, status};
+use crate::{config::Config, listener::Listener, state::AppState};
+use crate::handlers::{admin, chat, generate, models, health, metrics, openai, status};
 
 pub struct Server {
     config: Config,
@@ -26,18 +26,30 @@ impl Server {
     }
 
     pub async fn run(self) -> Result<()> {
+        if let Err(err) = crate::metrics::init_otlp_exporter(&self.config.telemetry) {
+            tracing::warn!("Failed to initialize OTLP metrics exporter: {err}");
+        }
+
+        if let Some(model_id) = self.config.models.default_model.clone() {
+            match self
+                .state
+                .load_model(&model_id, Some(&self.config.models.default_quantization))
+                .await
+            {
+                Ok(_) => info!("Preloaded default model '{model_id}'"),
+                Err(err) => {
+                    tracing::warn!("Failed to preload default model '{model_id}': {err}")
+                }
+            }
+        }
+
         let app = self.create_router();
-        
-        let addr = SocketAddr::new(
-            self.config.server.bind_address.parse()?, 
-            self.config.server.port
-        );
-        
-        info!("Starting server on {}", addr);
-        
-        let listener = tokio::net::TcpListener::bind(addr).await?;
+
+        let listener = Listener::bind(&self.config.server)?;
+        info!("Starting server on {}", self.config.server.bind_address);
+
         axum::serve(listener, app).await?;
-        
+
         Ok(())
     }
 
@@ -48,8 +60,26 @@ impl Server {
             .allow_methods(tower_http::cors::Any)
             .allow_headers(tower_http::cors::Any);
 
+        // Streams gzip/deflate chunk-by-chunk rather than buffering the
+        // whole body, so it composes with the token-streaming responses
+        // from `/api/v1/generate` and `/api/v1/chat`. It negotiates against
+        // `Accept-Encoding`, sets `Content-Encoding`/`Vary: Accept-Encoding`
+        // on what it compresses, and leaves already-upgraded connections
+        // (the `/ws`-style 101 response) and bodies under the configured
+        // threshold alone.
+        let compression_config = &self.config.server.compression;
+        let compression = CompressionLayer::new()
+            .gzip(compression_config.gzip)
+            .deflate(compression_config.deflate)
+            .br(false)
+            .zstd(false)
+            .compress_when(SizeAbove::new(
+                compression_config.min_size_bytes.min(u16::MAX as usize) as u16,
+            ));
+
         Router::new()
             .route("/api/v1/health", get(health::health_check))
+            .route("/metrics", get(metrics::metrics_handler))
             .route("/api/v1/status", get(status::server_status))
             .route("/api/v1/models", get(models::list_models))
             .route("/api/v1/models/:model_id/load", axum::routing::post(models::load_model))
@@ -57,7 +87,15 @@ impl Server {
             .route("/api/v1/chat", axum::routing::post(chat::chat_handler))
             .route("/api/v1/generate", axum::routing::post(generate::generate_handler))
             .route("/v1/models", axum::routing::get(openai::list_openai_models))
+            .route(
+                "/admin/models/:model_id",
+                axum::routing::get(admin::model_details)
+                    .post(admin::load_model)
+                    .delete(admin::unload_model),
+            )
+            .route("/admin/config", axum::routing::put(admin::reload_cloud_config))
             .layer(cors)
+            .layer(compression)
             .layer(TraceLayer::new_for_http())
             .layer(RequestBodyLimitLayer::new(self.config.limits.max_request_size))
                         .with_state(self.state.clone())
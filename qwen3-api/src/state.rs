@@ -1,4 +1,3 @@
-use anyhow::Result;
 use dashmap::DashMap;
 use qwen3_inference::ExtendedTransformer;
 use std::path::PathBuf;
@@ -6,6 +5,8 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 use serde::{Deserialize, Serialize};
 
+use crate::error::ApiError;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModelInfo {
     pub id: String,
@@ -25,21 +26,59 @@ pub struct LoadedModel {
     pub request_count: std::sync::atomic::AtomicU64,
     pub total_tokens_generated: std::sync::atomic::AtomicU64,
     pub last_inference_at: Option<std::time::Instant>,
+    /// Admission-controls KV-cache bytes against `memory_limits.max_memory_mb`
+    /// across every request this model is currently serving.
+    pub memory_limiter: qwen3_inference::MemoryLimiter,
+    /// Caps concurrent in-flight generations at `memory_limits.max_batch_size`;
+    /// requests beyond that back off with 503 instead of queuing unbounded.
+    pub batch_semaphore: Arc<tokio::sync::Semaphore>,
+    /// Original permit count `batch_semaphore` was created with, so eviction
+    /// can tell "some permits are held" (`available_permits() < max_batch_size`)
+    /// apart from "no request is in flight right now".
+    pub max_batch_size: usize,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct AppState {
     pub config: crate::config::Config,
     pub models: Arc<DashMap<String, LoadedModel>>,
     pub active_requests: Arc<std::sync::atomic::AtomicUsize>,
+    pub resource_monitor: Arc<crate::monitor::ResourceMonitor>,
+    /// Multi-provider cloud router `PUT /admin/config` hot-reloads. `None`
+    /// until `admin.cloud` is configured (at startup or via that endpoint).
+    pub cloud_manager: Arc<RwLock<Option<qwen3_inference::cloud::CloudManager>>>,
+}
+
+impl std::fmt::Debug for AppState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AppState")
+            .field("config", &self.config)
+            .field("models", &self.models)
+            .field("active_requests", &self.active_requests)
+            .finish_non_exhaustive()
+    }
 }
 
 impl AppState {
     pub fn new(config: crate::config::Config) -> Self {
+        let cloud_manager = config
+            .admin
+            .cloud
+            .clone()
+            .and_then(|cloud_config| match qwen3_inference::cloud::CloudManager::new(cloud_config) {
+                Ok(manager) => Some(manager),
+                Err(err) => {
+                    tracing::warn!("Failed to initialize cloud manager from config: {err}");
+                    None
+                }
+            });
+
         Self {
             config,
             models: Arc::new(DashMap::new()),
             active_requests: Arc::new(std::sync::atomic::AtomicUsize::new(0)),
+            resource_monitor: Arc::new(crate::monitor::ResourceMonitor::new()),
+            cloud_manager: Arc::new(RwLock::new(cloud_manager)),
         }
     }
 
@@ -47,11 +86,11 @@ impl AppState {
         &self,
         model_id: &str,
         quantization: Option<&str>,
-    ) -> Result<String> {
+    ) -> Result<String, ApiError> {
         let model_path = PathBuf::from(&self.config.models.directory).join(format!("{}.bin", model_id));
-        
+
         if !model_path.exists() {
-            anyhow::bail!("Model file not found: {:?}", model_path);
+            return Err(ApiError::ModelNotFound(format!("{:?}", model_path)));
         }
 
         let transformer = ExtendedTransformer::new(&model_path)?;
@@ -65,6 +104,8 @@ impl AppState {
             context_window: self.config.models.context_window,
         };
 
+        let memory_limits = transformer.config.memory_limits;
+
         let loaded_model = LoadedModel {
             info: info.clone(),
             transformer: Arc::new(RwLock::new(transformer)),
@@ -73,6 +114,9 @@ impl AppState {
             request_count: std::sync::atomic::AtomicU64::new(0),
             total_tokens_generated: std::sync::atomic::AtomicU64::new(0),
             last_inference_at: None,
+            memory_limiter: qwen3_inference::MemoryLimiter::new(memory_limits.max_memory_mb),
+            batch_semaphore: Arc::new(tokio::sync::Semaphore::new(memory_limits.max_batch_size)),
+            max_batch_size: memory_limits.max_batch_size,
         };
 
         self.models.insert(model_id.to_string(), loaded_model);
@@ -81,9 +125,9 @@ impl AppState {
     }
 
     pub fn unload_model(&self, model_id: &str
-    ) -> Result<()> {
+    ) -> Result<(), ApiError> {
         if self.models.remove(model_id).is_none() {
-            anyhow::bail!("Model not found: {}", model_id);
+            return Err(ApiError::ModelNotFound(model_id.to_string()));
         }
         Ok(())
     }
@@ -104,6 +148,9 @@ impl AppState {
                     loaded_model.total_tokens_generated.load(std::sync::atomic::Ordering::Relaxed)
                 ),
                 last_inference_at: loaded_model.last_inference_at,
+                memory_limiter: loaded_model.memory_limiter.clone(),
+                batch_semaphore: loaded_model.batch_semaphore.clone(),
+                max_batch_size: loaded_model.max_batch_size,
             })
         })
     }
@@ -116,24 +163,68 @@ impl AppState {
             .collect()
     }
 
-    pub fn enforce_model_limits(&self) -> Result<()> {
-        if self.models.len() > self.config.models.max_loaded_models {
-            // Simple LRU eviction - remove oldest model
-            let mut oldest = None;
-            let mut oldest_time = std::time::Instant::now();
-            
-            for entry in self.models.iter() {
-                if entry.last_used < oldest_time {
-                    oldest = Some(entry.key().clone());
-                    oldest_time = entry.last_used;
-                }
+    /// Evicts the least-recently-used model that isn't currently serving a
+    /// request, so `enforce_model_limits` can keep calling this until either
+    /// the count/budget constraint is satisfied or every loaded model is
+    /// busy. Returns `Ok(false)` instead of erroring when nothing is
+    /// evictable, letting the caller stop the loop gracefully.
+    fn evict_least_recently_used(&self) -> Result<bool, ApiError> {
+        let mut oldest = None;
+        let mut oldest_time = std::time::Instant::now();
+
+        for entry in self.models.iter() {
+            let is_serving = entry.batch_semaphore.available_permits() < entry.max_batch_size;
+            if is_serving {
+                continue;
+            }
+            let last_active = entry.last_inference_at.unwrap_or(entry.last_used);
+            if last_active < oldest_time {
+                oldest = Some((entry.key().clone(), entry.info.size));
+                oldest_time = last_active;
             }
-            
-            if let Some(model_id) = oldest {
+        }
+
+        match oldest {
+            Some((model_id, size)) => {
                 self.unload_model(&model_id)?;
-                tracing::info!("Evicted model {} due to memory limits", model_id);
+                tracing::info!(
+                    "Evicted model {} (freed {} bytes) to satisfy model limits",
+                    model_id,
+                    size
+                );
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    fn total_loaded_bytes(&self) -> u64 {
+        self.models.iter().map(|entry| entry.info.size).sum()
+    }
+
+    /// Makes room for a model of `incoming_model_size` bytes by evicting
+    /// least-recently-used models, first against the `max_loaded_models`
+    /// count cap and then, if `max_total_memory_bytes` is configured,
+    /// against the combined `ModelInfo.size` of every loaded model. Models
+    /// with an in-flight request (a held `batch_semaphore` permit) are
+    /// skipped so eviction never interrupts active inference; if every
+    /// loaded model is busy, the caller proceeds with whatever limit is
+    /// still exceeded rather than blocking admission.
+    pub fn enforce_model_limits(&self, incoming_model_size: u64) -> Result<(), ApiError> {
+        while self.models.len() >= self.config.models.max_loaded_models {
+            if !self.evict_least_recently_used()? {
+                break;
+            }
+        }
+
+        if let Some(budget) = self.config.models.max_total_memory_bytes {
+            while self.total_loaded_bytes() + incoming_model_size > budget {
+                if !self.evict_least_recently_used()? {
+                    break;
+                }
             }
         }
+
         Ok(())
     }
 }
\ No newline at end of file
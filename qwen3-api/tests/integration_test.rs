@@ -88,6 +88,38 @@ async fn test_generate_endpoint() {
     assert_eq!(response.status(), StatusCode::NOT_FOUND);
 }
 
+#[tokio::test]
+async fn test_generate_endpoint_stream_flag() {
+    let config = Config::default();
+    let server = Server::new(config);
+    let app = server.create_router();
+
+    let request_body = json!({
+        "model": "test-model",
+        "prompt": "Hello, world!",
+        "max_tokens": 10,
+        "stream": true
+    });
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/v1/generate")
+                .header("content-type", "application/json")
+                .body(Body::from(request_body.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    // Still 404 (no model loaded) rather than erroring on the new `stream`
+    // field itself, so admission control runs before the SSE branch is
+    // reached. A full `text/event-stream` + `data:` frame assertion needs a
+    // real model fixture to load, which this test suite doesn't carry.
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
 #[tokio::test]
 async fn test_chat_endpoint() {
     let config = Config::default();
@@ -146,6 +178,35 @@ async fn test_cors_headers() {
     assert_eq!(response.headers()["access-control-allow-origin"], "*");
 }
 
+#[tokio::test]
+async fn test_metrics_endpoint() {
+    let config = Config::default();
+    let server = Server::new(config);
+    let app = server.create_router();
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/metrics")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers()["content-type"],
+        "text/plain; version=0.0.4"
+    );
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let text = String::from_utf8(body.to_vec()).unwrap();
+    assert!(text.contains("qwen3_active_requests"));
+}
+
 #[tokio::test]
 async fn test_invalid_json_returns_422() {
     let config = Config::default();
@@ -1,7 +1,7 @@
 use std::path::Path;
 
 use anyhow::Result;
-use clap::{Arg, ArgMatches, Command};
+use clap::{Arg, ArgAction, ArgMatches, Command};
 use log::{debug, error, info};
 use qwen3_export::{export_model, load_hf_config};
 use qwen3_inference::{InferenceConfigBuilder, run_inference};
@@ -24,6 +24,11 @@ fn export_subcommand() -> Command {
             .help("Quantization group size")
             .value_name("SIZE")
             .default_value("64"))
+        .arg(Arg::new("container")
+            .long("container")
+            .value_name("FORMAT")
+            .help("Header format: raw (fixed-layout, default) | bincode | msgpack (self-describing, forward-compatible metadata)")
+            .default_value("raw"))
 }
 
 /// Define the inference subcommand.
@@ -123,6 +128,12 @@ fn models_subcommand() -> Command {
                 .help("Output format: table|json|list [default: table]")
                 .default_value("table"),
         )
+        .arg(
+            Arg::new("verify")
+                .long("verify")
+                .help("Additionally check each file's length against its declared tensor sizes")
+                .action(ArgAction::SetTrue),
+        )
 }
 
 /// Run the export command with the provided arguments
@@ -134,6 +145,8 @@ fn run_export_command(matches: &ArgMatches) -> Result<()> {
         .unwrap()
         .parse()
         .map_err(|_| anyhow::anyhow!("Invalid group size"))?;
+    let container: qwen3_inference::ContainerFormat =
+        matches.get_one::<String>("container").unwrap().parse()?;
 
     // Validate input path
     let model_dir = Path::new(model_path);
@@ -182,7 +195,7 @@ fn run_export_command(matches: &ArgMatches) -> Result<()> {
     debug!("{config:#?}");
 
     // Create exporter and run the export
-    export_model(model_path, output_path, config, group_size)?;
+    export_model(model_path, output_path, config, group_size, container)?;
 
     Ok(())
 }
@@ -212,7 +225,12 @@ struct ModelInfo {
     name: String,
     path: String,
     size: u64,
-    format: String,
+    quantization: String,
+    vocab_size: Option<usize>,
+    dim: Option<usize>,
+    n_layers: Option<usize>,
+    seq_len: Option<usize>,
+    status: String,
     modified: String,
 }
 
@@ -220,8 +238,9 @@ struct ModelInfo {
 fn run_models_command(matches: &ArgMatches) -> Result<()> {
     let directory = matches.get_one::<String>("directory").unwrap();
     let format = matches.get_one::<String>("format").unwrap();
+    let verify = matches.get_flag("verify");
 
-    let models = discover_models(directory)?;
+    let models = discover_models(directory, verify)?;
 
     match format.as_str() {
         "json" => {
@@ -234,22 +253,23 @@ fn run_models_command(matches: &ArgMatches) -> Result<()> {
         }
         "table" | _ => {
             println!(
-                "┌─────────────────────────────────────────┬────────────────┬────────────┬────────┬─────────────────────┐"
+                "┌─────────────────────────────────────┬──────────────┬────────────┬────────────────────┬────────┬─────────────────────┐"
             );
             println!(
-                "│ Model Name                              │ Size           │ Format     │ Path   │ Modified            │"
+                "│ Model Name                          │ Size         │ Quant.     │ Status             │ Path   │ Modified            │"
             );
             println!(
-                "├─────────────────────────────────────────┼────────────────┼────────────┼────────┼─────────────────────┤"
+                "├─────────────────────────────────────┼──────────────┼────────────┼────────────────────┼────────┼─────────────────────┤"
             );
 
             for model in models {
                 let size_mb = model.size as f64 / (1024.0 * 1024.0);
                 println!(
-                    "│ {:<39} │ {:<14} │ {:<10} │ {:<6} │ {:<19} │",
+                    "│ {:<37} │ {:<12} │ {:<10} │ {:<18} │ {:<6} │ {:<19} │",
                     model.name,
                     format!("{:.1} MB", size_mb),
-                    model.format,
+                    model.quantization,
+                    model.status,
                     if model.path.contains("HuggingFace") {
                         "HF"
                     } else {
@@ -260,7 +280,7 @@ fn run_models_command(matches: &ArgMatches) -> Result<()> {
             }
 
             println!(
-                "└─────────────────────────────────────────┴────────────────┴────────────┴────────┴─────────────────────┘"
+                "└─────────────────────────────────────┴──────────────┴────────────┴────────────────────┴────────┴─────────────────────┘"
             );
         }
     }
@@ -268,8 +288,133 @@ fn run_models_command(matches: &ArgMatches) -> Result<()> {
     Ok(())
 }
 
+/// Header-verified identity of one checkpoint file, read straight out of
+/// its magic number / version / `ModelConfig`, not guessed from the
+/// filename. `status` is one of `"ok"`, `"bad magic"`, `"unsupported
+/// version"`, or `"truncated"` so a corrupt or mismatched file doesn't show
+/// up looking like a healthy model.
+struct CheckpointHeader {
+    status: &'static str,
+    quantization: String,
+    vocab_size: Option<usize>,
+    dim: Option<usize>,
+    n_layers: Option<usize>,
+    seq_len: Option<usize>,
+}
+
+impl CheckpointHeader {
+    fn bad(status: &'static str, quantization_guess: String) -> Self {
+        Self {
+            status,
+            quantization: quantization_guess,
+            vocab_size: None,
+            dim: None,
+            n_layers: None,
+            seq_len: None,
+        }
+    }
+}
+
+/// Guesses a model's quantization from its filename, used as a fallback
+/// when the checkpoint doesn't carry a self-describing metadata header
+/// (the old fixed-layout `raw` container) or couldn't be parsed at all.
+fn quantization_guess_from_name(name: &str) -> String {
+    if name.contains("int4") {
+        "INT4"
+    } else if name.contains("int8") {
+        "INT8"
+    } else if name.contains("fp16") {
+        "FP16"
+    } else {
+        "BINARY"
+    }
+    .to_string()
+}
+
+/// Opens `path`, verifies the checkpoint magic number and version, and
+/// parses the real `ModelConfig` header so `qwen3 models` reports what's
+/// actually in the file instead of a guess from its name. When `verify` is
+/// set and the file carries a weight table (see `weight_table.rs`), also
+/// checks the file's length against the sum of the declared tensor sizes,
+/// catching a truncated or hand-edited `.bin` before a user tries to run
+/// inference against it.
+fn inspect_checkpoint(path: &Path, verify: bool, name: &str) -> CheckpointHeader {
+    let quantization_guess = || quantization_guess_from_name(name);
+
+    let file = match std::fs::File::open(path) {
+        Ok(f) => f,
+        Err(_) => return CheckpointHeader::bad("truncated", quantization_guess()),
+    };
+    let mut mapper = match qwen3_inference::utils::MemoryMapper::new(file) {
+        Ok(m) => m,
+        Err(_) => return CheckpointHeader::bad("truncated", quantization_guess()),
+    };
+
+    let Some(magic_bytes) = mapper.as_bytes().get(0..4) else {
+        return CheckpointHeader::bad("truncated", quantization_guess());
+    };
+    let magic = i32::from_le_bytes(magic_bytes.try_into().unwrap());
+    if magic != 0x5157454E {
+        return CheckpointHeader::bad("bad magic", quantization_guess());
+    }
+
+    let Some(version_bytes) = mapper.as_bytes().get(4..8) else {
+        return CheckpointHeader::bad("truncated", quantization_guess());
+    };
+    let version = i32::from_le_bytes(version_bytes.try_into().unwrap());
+
+    if version == 2 || version == 3 {
+        return match qwen3_inference::read_model_metadata(&mut mapper) {
+            Ok(metadata) => CheckpointHeader {
+                status: "ok",
+                quantization: metadata.quantization.to_uppercase(),
+                vocab_size: Some(metadata.config.vocab_size),
+                dim: Some(metadata.config.dim),
+                n_layers: Some(metadata.config.n_layers),
+                seq_len: Some(metadata.config.seq_len),
+            },
+            Err(_) => CheckpointHeader::bad("truncated", quantization_guess()),
+        };
+    }
+
+    if version != 1 {
+        return CheckpointHeader::bad("unsupported version", quantization_guess());
+    }
+
+    let config = match qwen3_inference::configuration::read_config(&mut mapper) {
+        Ok(config) => config,
+        Err(_) => return CheckpointHeader::bad("truncated", quantization_guess()),
+    };
+
+    if verify {
+        if let Ok(table) = qwen3_inference::configuration::read_weight_table(&mapper) {
+            let declared: u64 = table.tensors.iter().map(|t| t.length).sum();
+            let actual = mapper.as_bytes().len() as u64 - mapper.offset() as u64;
+            if declared != actual {
+                return CheckpointHeader {
+                    status: "truncated",
+                    quantization: quantization_guess(),
+                    vocab_size: Some(config.vocab_size),
+                    dim: Some(config.dim),
+                    n_layers: Some(config.n_layers),
+                    seq_len: Some(config.seq_len),
+                };
+            }
+        }
+    }
+
+    CheckpointHeader {
+        status: "ok",
+        quantization: quantization_guess(),
+        vocab_size: Some(config.vocab_size),
+        dim: Some(config.dim),
+        n_layers: Some(config.n_layers),
+        seq_len: Some(config.seq_len),
+    }
+}
+
 /// Discover available models in the specified directory
-fn discover_models(directory: &str) -> Result<Vec<ModelInfo>> {
+fn discover_models(directory: &str, verify: bool) -> Result<Vec<ModelInfo>> {
     let mut models = Vec::new();
 
     // Expand tilde if present
@@ -301,16 +446,7 @@ fn discover_models(directory: &str) -> Result<Vec<ModelInfo>> {
                         .unwrap_or("unknown")
                         .to_string();
 
-                    let format = if name.contains("int4") {
-                        "INT4"
-                    } else if name.contains("int8") {
-                        "INT8"
-                    } else if name.contains("fp16") {
-                        "FP16"
-                    } else {
-                        "BINARY"
-                    }
-                    .to_string();
+                    let header = inspect_checkpoint(&file_path, verify, &name);
 
                     let modified = metadata
                         .modified()
@@ -343,7 +479,12 @@ fn discover_models(directory: &str) -> Result<Vec<ModelInfo>> {
                         name,
                         path: file_path.to_string_lossy().to_string(),
                         size: metadata.len(),
-                        format,
+                        quantization: header.quantization,
+                        vocab_size: header.vocab_size,
+                        dim: header.dim,
+                        n_layers: header.n_layers,
+                        seq_len: header.seq_len,
+                        status: header.status.to_string(),
                         modified,
                     });
                 }
@@ -3,9 +3,12 @@
 //! Provides safe Rust wrappers around the C API for loading and running
 //! Qwen3 models with maximum CPU performance.
 
+use std::alloc::Layout;
 use std::ffi::{CString, CStr};
 use std::os::raw::{c_char, c_uint};
 
+use qwen3_inference::{CpuInfo, OptimizationStrategy};
+
 // Link to the C library
 #[link(name = "qwen3_inference")]
 unsafe extern "C" {
@@ -18,7 +21,17 @@ unsafe extern "C" {
     fn qwen3_model_get_config(model: *const Qwen3Model) -> *const Qwen3ModelConfig;
     fn qwen3_model_validate(model: *const Qwen3Model) -> bool;
     fn qwen3_model_get_info(model: *const Qwen3Model) -> *const c_char;
-    
+
+    // Streaming generation
+    fn qwen3_generate_init(
+        model: *mut Qwen3Model,
+        prompt_tokens: *const u32,
+        prompt_len: c_uint,
+        sampling: *const Qwen3SamplingConfig,
+    ) -> *mut Qwen3GenerationSession;
+    fn qwen3_generate_step(session: *mut Qwen3GenerationSession, out_token: *mut u32) -> bool;
+    fn qwen3_generate_free(session: *mut Qwen3GenerationSession);
+
     // Error handling
     fn qwen3_get_last_error() -> *const c_char;
 }
@@ -53,11 +66,136 @@ pub struct Qwen3Model {
     _private: [u8; 0],
 }
 
+#[repr(C)]
+pub struct Qwen3GenerationSession {
+    _private: [u8; 0],
+}
+
+/// Sampling parameters for a generation session, mirrored 1:1 with the C
+/// `qwen3_sampling_config_t` so it can be passed by pointer across the FFI
+/// boundary without per-call marshalling.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct Qwen3SamplingConfig {
+    pub temperature: f32,
+    pub top_p: f32,
+    pub top_k: u32,
+    pub seed: u64,
+}
+
+/// Sentinel written to `out_token` by `qwen3_generate_step` to signal
+/// end-of-sequence, distinct from any real vocabulary id.
+pub const QWEN3_EOS_TOKEN: u32 = u32::MAX;
+
 /// Safe Rust wrapper for Qwen3 model
 pub struct Qwen3ModelHandle {
     model: *mut Qwen3Model,
+    /// Backs `LoadOptions::use_memory_pool`: a single aligned block sized to
+    /// the worst-case per-step working set, allocated once at load and handed
+    /// out through [`Self::scratch_arena`]. `generate` does not pass it to
+    /// the C engine.
+    scratch: Option<ScratchArena>,
+}
+
+/// A single aligned block of scratch memory, grown once to the worst-case
+/// working-set size at load time and bump-allocated from on each generation
+/// step. Callers `reset()` the bump pointer between tokens (or prompts)
+/// rather than freeing, which keeps the hot decode loop allocation-free.
+pub struct ScratchArena {
+    ptr: std::ptr::NonNull<u8>,
+    layout: Layout,
+    offset: usize,
+}
+
+impl ScratchArena {
+    /// Allocates a `capacity`-byte block aligned to `alignment` (typically
+    /// `OptimizationStrategy::alignment`, so SIMD loads/stores out of the
+    /// arena never need an unaligned path).
+    pub fn new(capacity: usize, alignment: usize) -> anyhow::Result<Self> {
+        // `GlobalAlloc::alloc` requires a non-zero-size layout; a degenerate
+        // (e.g. corrupt/truncated) checkpoint can drive `worst_case_size` to
+        // 0, so reject that here rather than handing `std::alloc::alloc` a
+        // zero-size layout, which is documented UB.
+        if capacity == 0 {
+            anyhow::bail!("Scratch arena capacity must be greater than zero");
+        }
+
+        let alignment = alignment.max(std::mem::align_of::<f32>());
+        let layout = Layout::from_size_align(capacity, alignment)
+            .map_err(|e| anyhow::anyhow!("Invalid scratch arena layout: {e}"))?;
+
+        let raw = unsafe { std::alloc::alloc(layout) };
+        let ptr = std::ptr::NonNull::new(raw)
+            .ok_or_else(|| anyhow::anyhow!("Failed to allocate {capacity}-byte scratch arena"))?;
+
+        Ok(Self {
+            ptr,
+            layout,
+            offset: 0,
+        })
+    }
+
+    /// Computes the worst-case per-step scratch size (logits + per-layer
+    /// attention/activation buffers) for a model, so the arena never needs
+    /// to grow after load.
+    pub fn worst_case_size(config: &Qwen3ModelConfig) -> usize {
+        let dim = config.dim as usize;
+        let n_layers = config.n_layers as usize;
+        let n_kv_heads = config.n_kv_heads as usize;
+        let head_dim = config.head_dim as usize;
+        let vocab_size = config.vocab_size as usize;
+        let max_seq_len = config.max_seq_len as usize;
+
+        let logits = vocab_size * std::mem::size_of::<f32>();
+        let activations = dim * std::mem::size_of::<f32>();
+        let attention_scratch =
+            n_layers * n_kv_heads * head_dim * max_seq_len * std::mem::size_of::<f32>();
+
+        logits + activations + attention_scratch
+    }
+
+    /// Bump-allocates `size` bytes out of the arena, returning a pointer
+    /// valid until the next `reset()`. Fails if the arena's fixed capacity
+    /// (computed once at load time) would be exceeded.
+    pub fn bump_alloc(&mut self, size: usize) -> anyhow::Result<*mut u8> {
+        let align = self.layout.align();
+        let aligned_offset = (self.offset + align - 1) & !(align - 1);
+
+        if aligned_offset + size > self.layout.size() {
+            anyhow::bail!(
+                "Scratch arena exhausted: requested {size} bytes at offset {aligned_offset}, capacity is {}",
+                self.layout.size()
+            );
+        }
+
+        self.offset = aligned_offset + size;
+        Ok(unsafe { self.ptr.as_ptr().add(aligned_offset) })
+    }
+
+    /// Rewinds the bump pointer to the start of the arena so the next
+    /// generation step (or prompt) can reuse the same block.
+    pub fn reset(&mut self) {
+        self.offset = 0;
+    }
+
+    /// Total arena size in bytes.
+    pub fn capacity(&self) -> usize {
+        self.layout.size()
+    }
 }
 
+impl Drop for ScratchArena {
+    fn drop(&mut self) {
+        unsafe {
+            std::alloc::dealloc(self.ptr.as_ptr(), self.layout);
+        }
+    }
+}
+
+// Safety: the arena is only ever accessed through `&mut Qwen3ModelHandle`,
+// never shared across threads concurrently.
+unsafe impl Send for ScratchArena {}
+
 impl Qwen3ModelHandle {
     /// Load a model from file
     pub fn load(checkpoint_path: &str, context_length: Option<u32>) -> anyhow::Result<Self> {
@@ -73,21 +211,24 @@ impl Qwen3ModelHandle {
             };
             anyhow::bail!("Failed to load model: {}", error);
         }
-        
-        Ok(Self { model })
+
+        Ok(Self {
+            model,
+            scratch: None,
+        })
     }
-    
+
     /// Load a model with detailed options
     pub fn load_with_options(options: LoadOptions) -> anyhow::Result<Self> {
         let path_c = CString::new(options.checkpoint_path)?;
-        
+
         let c_options = Qwen3LoadOptions {
             checkpoint_path: path_c.as_ptr(),
             context_length: options.context_length.unwrap_or(0),
             validate_weights: options.validate_weights,
             use_memory_pool: options.use_memory_pool,
         };
-        
+
         let model = unsafe { qwen3_model_load_ex(&c_options) };
         if model.is_null() {
             let error = unsafe {
@@ -97,8 +238,26 @@ impl Qwen3ModelHandle {
             };
             anyhow::bail!("Failed to load model: {}", error);
         }
-        
-        Ok(Self { model })
+
+        let mut handle = Self {
+            model,
+            scratch: None,
+        };
+
+        if options.use_memory_pool {
+            let config = handle.config()?;
+            let capacity = ScratchArena::worst_case_size(&config);
+            let alignment = OptimizationStrategy::for_cpu(&CpuInfo::detect()).alignment;
+            handle.scratch = Some(ScratchArena::new(capacity, alignment)?);
+        }
+
+        Ok(handle)
+    }
+
+    /// The model's pre-allocated scratch arena, present when this handle was
+    /// loaded with `LoadOptions::use_memory_pool`.
+    pub fn scratch_arena(&mut self) -> Option<&mut ScratchArena> {
+        self.scratch.as_mut()
     }
     
     /// Get model configuration
@@ -122,7 +281,7 @@ impl Qwen3ModelHandle {
         if info_ptr.is_null() {
             anyhow::bail!("Failed to get model info");
         }
-        
+
         let info = unsafe {
             CStr::from_ptr(info_ptr)
                 .to_string_lossy()
@@ -130,6 +289,77 @@ impl Qwen3ModelHandle {
         };
         Ok(info)
     }
+
+    /// Starts a streaming generation session over `prompt_tokens`, yielding
+    /// one token id at a time via the returned iterator rather than
+    /// buffering a whole completion before it can be sent to a caller (e.g.
+    /// the `qwen3_web` chat UI).
+    pub fn generate(
+        &mut self,
+        prompt_tokens: &[u32],
+        sampling: Qwen3SamplingConfig,
+    ) -> anyhow::Result<GenerationSession<'_>> {
+        let session = unsafe {
+            qwen3_generate_init(
+                self.model,
+                prompt_tokens.as_ptr(),
+                prompt_tokens.len() as c_uint,
+                &sampling,
+            )
+        };
+
+        if session.is_null() {
+            let error = unsafe {
+                CStr::from_ptr(qwen3_get_last_error())
+                    .to_string_lossy()
+                    .into_owned()
+            };
+            anyhow::bail!("Failed to start generation session: {}", error);
+        }
+
+        Ok(GenerationSession {
+            session,
+            finished: false,
+            _model: std::marker::PhantomData,
+        })
+    }
+}
+
+/// Owns a C-side generation session and yields sampled token ids one step
+/// at a time. Dropping it frees the session; exhausting the iterator only
+/// stops further steps.
+pub struct GenerationSession<'model> {
+    session: *mut Qwen3GenerationSession,
+    finished: bool,
+    _model: std::marker::PhantomData<&'model mut Qwen3ModelHandle>,
+}
+
+impl Iterator for GenerationSession<'_> {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        if self.finished {
+            return None;
+        }
+
+        let mut token = 0u32;
+        let produced = unsafe { qwen3_generate_step(self.session, &mut token) };
+
+        if !produced || token == QWEN3_EOS_TOKEN {
+            self.finished = true;
+            return None;
+        }
+
+        Some(token)
+    }
+}
+
+impl Drop for GenerationSession<'_> {
+    fn drop(&mut self) {
+        unsafe {
+            qwen3_generate_free(self.session);
+        }
+    }
 }
 
 impl Drop for Qwen3ModelHandle {
@@ -202,4 +432,9 @@ mod tests {
         assert_eq!(options.validate_weights, true);
         assert_eq!(options.use_memory_pool, false);
     }
+
+    #[test]
+    fn test_scratch_arena_rejects_zero_capacity() {
+        assert!(ScratchArena::new(0, 16).is_err());
+    }
 }
\ No newline at end of file
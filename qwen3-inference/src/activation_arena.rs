@@ -0,0 +1,137 @@
+//! Preallocated scratch-buffer arena for the hot inference path.
+//!
+//! Hidden-state, attention-score and GEMM-tile scratch buffers are sized
+//! once at build time from `config.base` dimensions and
+//! `memory_limits.max_context_length`, rounded up to
+//! [`OptimizationStrategy::alignment`] elements, and laid out back to back
+//! in one `Vec<f32>`, whose start is only guaranteed `f32` alignment.
+//! [`ExtendedTransformer`](crate::extended_transformer::ExtendedTransformer)
+//! owns one and hands it out through `activation_arena()`; the transformer's
+//! own forward pass does not draw from it.
+
+use crate::cpu_optimizations::OptimizationStrategy;
+use crate::extended_config::ExtendedModelConfig;
+
+/// Rounds `len` elements up to the nearest multiple of `alignment` elements,
+/// so each region's offset from the start of the buffer is a multiple of
+/// `alignment` elements.
+fn round_up(len: usize, alignment: usize) -> usize {
+    let alignment = alignment.max(1);
+    len.div_ceil(alignment) * alignment
+}
+
+/// One contiguous scratch allocation holding the hidden-state,
+/// attention-score and GEMM-tile regions, allocated once when built.
+#[derive(Debug, Clone)]
+pub struct ActivationArena {
+    hidden_state_len: usize,
+    attention_scores_len: usize,
+    gemm_tile_len: usize,
+    storage: Vec<f32>,
+}
+
+impl ActivationArena {
+    /// Computes worst-case region sizes from `config.base` and
+    /// `memory_limits.max_context_length`, rounds each up to
+    /// `strategy.alignment`, and allocates them as one contiguous buffer.
+    pub fn new(config: &ExtendedModelConfig, strategy: &OptimizationStrategy) -> Self {
+        let dim = config.base.dim;
+        let ctx_len = config.memory_limits.max_context_length;
+        let alignment = strategy.alignment;
+
+        // Worst-case per-token hidden state: one row of width `dim`.
+        let hidden_state_len = round_up(dim, alignment);
+        // Worst-case attention-score matrix for one head: every query
+        // position against every cached key position.
+        let attention_scores_len = round_up(ctx_len * ctx_len, alignment);
+        // Worst-case GEMM tile scratch, from the optimization strategy's
+        // chosen (m, n, k) tile.
+        let (tile_m, tile_n, tile_k) = strategy.gemm_tile_size();
+        let gemm_tile_len = round_up(tile_m * tile_n * tile_k, alignment);
+
+        let total_len = hidden_state_len + attention_scores_len + gemm_tile_len;
+
+        Self {
+            hidden_state_len,
+            attention_scores_len,
+            gemm_tile_len,
+            storage: vec![0.0; total_len],
+        }
+    }
+
+    /// Scratch space for one token's hidden state: `dim` elements rounded
+    /// up to `alignment`.
+    pub fn hidden_state(&mut self) -> &mut [f32] {
+        &mut self.storage[0..self.hidden_state_len]
+    }
+
+    /// Scratch space for one head's attention-score matrix: up to
+    /// `max_context_length^2` elements rounded up to `alignment`.
+    pub fn attention_scores(&mut self) -> &mut [f32] {
+        let start = self.hidden_state_len;
+        &mut self.storage[start..start + self.attention_scores_len]
+    }
+
+    /// Scratch space for one GEMM tile, sized from
+    /// [`OptimizationStrategy::gemm_tile_size`].
+    pub fn gemm_tile(&mut self) -> &mut [f32] {
+        let start = self.hidden_state_len + self.attention_scores_len;
+        &mut self.storage[start..start + self.gemm_tile_len]
+    }
+
+    /// Total footprint in bytes, folded into `MemoryStats::estimated_memory_mb`
+    /// by `ExtendedTransformer::memory_stats` so the arena counts against
+    /// the memory budget.
+    pub fn footprint_bytes(&self) -> usize {
+        self.storage.len() * std::mem::size_of::<f32>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configuration::ModelConfig;
+    use crate::quantization::CpuTarget;
+
+    fn test_config() -> ExtendedModelConfig {
+        let base = ModelConfig {
+            dim: 256,
+            hidden_dim: 1024,
+            n_layers: 4,
+            n_heads: 8,
+            n_kv_heads: 2,
+            head_dim: 32,
+            seq_len: 512,
+            vocab_size: 1000,
+            group_size: 32,
+            shared_classifier: true,
+            rope_theta: 10000.0,
+        };
+        let mut config = ExtendedModelConfig::for_cpu_target(base, CpuTarget::GenericX86);
+        config.memory_limits.max_context_length = 64;
+        config
+    }
+
+    #[test]
+    fn test_regions_are_aligned_and_non_overlapping() {
+        let config = test_config();
+        let strategy = OptimizationStrategy::for_cpu(&crate::cpu_optimizations::CpuInfo::detect());
+        let mut arena = ActivationArena::new(&config, &strategy);
+
+        assert_eq!(arena.hidden_state().len() % strategy.alignment, 0);
+        assert_eq!(arena.attention_scores().len() % strategy.alignment, 0);
+        assert_eq!(arena.gemm_tile().len() % strategy.alignment, 0);
+        assert!(arena.attention_scores().len() >= 64 * 64);
+    }
+
+    #[test]
+    fn test_footprint_matches_storage_size() {
+        let config = test_config();
+        let strategy = OptimizationStrategy::for_cpu(&crate::cpu_optimizations::CpuInfo::detect());
+        let arena = ActivationArena::new(&config, &strategy);
+
+        let expected = (arena.hidden_state_len + arena.attention_scores_len + arena.gemm_tile_len)
+            * std::mem::size_of::<f32>();
+        assert_eq!(arena.footprint_bytes(), expected);
+    }
+}
@@ -46,12 +46,10 @@ struct AnthropicUsage {
 }
 
 impl AnthropicProvider {
-    pub fn new(config: CloudProviderConfig) -> Self {
-        let client = reqwest::Client::builder()
-            .timeout(config.timeout)
-            .build()
-            .unwrap();
-
+    /// `client` is expected to be shared across providers by the caller
+    /// (see [`super::build_http_client`]) rather than built fresh per
+    /// provider; per-request timeouts still come from `config.timeout`.
+    pub fn new(config: CloudProviderConfig, client: reqwest::Client) -> Self {
         Self { config, client }
     }
 
@@ -68,20 +66,21 @@ impl AnthropicProvider {
             max_tokens: 512,
             temperature: 0.7,
             timeout: Duration::from_secs(30),
+            retries: 2,
+            dynamic_quant_guard: None,
         };
 
-        Some(Self::new(config))
+        let client = super::build_http_client(config.timeout).ok()?;
+        Some(Self::new(config, client))
     }
 }
 
 #[async_trait]
 impl CloudProvider for AnthropicProvider {
-    async fn generate(&self, prompt: &str, config: &InferenceConfig) -> Result<String> {
-        let api_key = self
-            .config
-            .api_key
-            .as_ref()
-            .ok_or_else(|| anyhow::anyhow!("Anthropic API key not configured"))?;
+    async fn generate(&self, prompt: &str, config: &InferenceConfig) -> Result<String, CloudError> {
+        let api_key = self.config.api_key.as_ref().ok_or_else(|| CloudError::Permanent {
+            message: "Anthropic API key not configured".to_string(),
+        })?;
 
         let request = AnthropicRequest {
             model: self.config.model.clone(),
@@ -100,25 +99,27 @@ impl CloudProvider for AnthropicProvider {
             .header("x-api-key", api_key)
             .header("Content-Type", "application/json")
             .header("anthropic-version", "2023-06-01")
+            .timeout(self.config.timeout)
             .json(&request)
             .send()
-            .await?;
+            .await
+            .map_err(classify_transport_error)?;
 
         if !response.status().is_success() {
-            return Err(anyhow::anyhow!(
-                "Anthropic API error: {}",
-                response.status()
-            ));
+            return Err(classify_http_error("Anthropic", response).await);
         }
 
-        let response_data: AnthropicResponse = response.json().await?;
+        let response_data: AnthropicResponse =
+            response.json().await.map_err(classify_transport_error)?;
 
         let content = response_data
             .content
             .iter()
             .find(|c| c.content_type == "text")
             .and_then(|c| c.text.clone())
-            .ok_or_else(|| anyhow::anyhow!("No text content in Anthropic response"))?;
+            .ok_or_else(|| CloudError::Permanent {
+                message: "No text content in Anthropic response".to_string(),
+            })?;
 
         Ok(content)
     }
@@ -133,6 +134,7 @@ impl CloudProvider for AnthropicProvider {
                 "x-api-key",
                 self.config.api_key.as_ref().unwrap_or(&"dummy".to_string()),
             )
+            .timeout(self.config.timeout)
             .send()
             .await
         {
@@ -168,6 +170,10 @@ impl CloudProvider for AnthropicProvider {
     fn get_name(&self) -> &str {
         &self.config.name
     }
+
+    fn retries(&self) -> u8 {
+        self.config.retries
+    }
 }
 
 #[cfg(test)]
@@ -184,9 +190,11 @@ mod tests {
             max_tokens: 512,
             temperature: 0.7,
             timeout: Duration::from_secs(30),
+            retries: 2,
+            dynamic_quant_guard: None,
         };
 
-        let provider = AnthropicProvider::new(config);
+        let provider = AnthropicProvider::new(config, reqwest::Client::new());
         assert_eq!(provider.get_name(), "anthropic");
     }
 
@@ -200,9 +208,11 @@ mod tests {
             max_tokens: 512,
             temperature: 0.7,
             timeout: Duration::from_secs(30),
+            retries: 2,
+            dynamic_quant_guard: None,
         };
 
-        let provider = AnthropicProvider::new(config);
+        let provider = AnthropicProvider::new(config, reqwest::Client::new());
         let cost = provider.get_cost_estimate(1000);
         assert!(cost > 0.0);
     }
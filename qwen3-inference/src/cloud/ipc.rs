@@ -0,0 +1,101 @@
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+/// One call over the IPC transport: either drive a generation or probe that
+/// the worker is alive and responding, mirroring the two things
+/// `LocalProvider` needs from a co-located inference process instead of an
+/// HTTP endpoint.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum IpcRequest {
+    Generate {
+        prompt: String,
+        max_tokens: usize,
+        temperature: f32,
+        top_p: f32,
+        frequency_penalty: f32,
+        presence_penalty: f32,
+    },
+    Ping,
+}
+
+/// One frame of the worker's reply. A `Generate` call gets a sequence of
+/// these with `done: false` until the final chunk sets `done: true`; `Ping`
+/// gets a single `done: true` frame with an empty `chunk`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IpcResponseFrame {
+    pub chunk: String,
+    pub done: bool,
+}
+
+/// Strips the `ipc:` scheme pico-qwen overlays on
+/// `CloudProviderConfig::base_url` to name a local worker's socket path (or,
+/// on Windows, its named pipe) instead of an HTTP endpoint.
+pub fn ipc_path(base_url: &str) -> Option<&str> {
+    base_url.strip_prefix("ipc:")
+}
+
+#[cfg(target_family = "unix")]
+async fn connect(path: &str) -> std::io::Result<tokio::net::UnixStream> {
+    tokio::net::UnixStream::connect(path).await
+}
+
+#[cfg(target_family = "windows")]
+async fn connect(path: &str) -> std::io::Result<tokio::net::windows::named_pipe::NamedPipeClient> {
+    tokio::net::windows::named_pipe::ClientOptions::new().open(path)
+}
+
+async fn write_frame<T: tokio::io::AsyncWrite + Unpin>(
+    io: &mut T,
+    request: &IpcRequest,
+) -> Result<()> {
+    let body = serde_json::to_vec(request)?;
+    io.write_all(&(body.len() as u32).to_le_bytes()).await?;
+    io.write_all(&body).await?;
+    Ok(())
+}
+
+/// A frame length is read straight off the wire before any parsing happens,
+/// so a misbehaving or compromised worker can claim up to `u32::MAX` bytes;
+/// cap it well above any real chunk so `vec![0u8; len]` can't be driven into
+/// an arbitrarily large allocation (see the same bug class fixed for
+/// `read_gguf_string` in `diagnostic_format.rs`).
+const MAX_IPC_FRAME_LEN: usize = 64 * 1024 * 1024;
+
+async fn read_frame<T: tokio::io::AsyncRead + Unpin>(io: &mut T) -> Result<IpcResponseFrame> {
+    let mut len_buf = [0u8; 4];
+    io.read_exact(&mut len_buf).await?;
+    let len = u32::from_le_bytes(len_buf) as usize;
+    if len > MAX_IPC_FRAME_LEN {
+        return Err(anyhow!(
+            "IPC frame length {len} exceeds the sane cap ({MAX_IPC_FRAME_LEN} bytes); \
+             worker is misbehaving or the stream is corrupted"
+        ));
+    }
+    let mut body = vec![0u8; len];
+    io.read_exact(&mut body).await?;
+    Ok(serde_json::from_slice(&body)?)
+}
+
+/// Sends one request to the worker listening at `path` and collects
+/// response frames until `done`, concatenating each frame's `chunk` — the
+/// IPC analogue of buffering an HTTP response body for callers that don't
+/// need to see partial output.
+pub async fn call(path: &str, request: IpcRequest) -> Result<String> {
+    let mut io = connect(path)
+        .await
+        .map_err(|err| anyhow!("failed to connect to IPC worker at {path}: {err}"))?;
+
+    write_frame(&mut io, &request).await?;
+
+    let mut output = String::new();
+    loop {
+        let frame = read_frame(&mut io).await?;
+        output.push_str(&frame.chunk);
+        if frame.done {
+            break;
+        }
+    }
+    Ok(output)
+}
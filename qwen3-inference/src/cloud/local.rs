@@ -1,34 +1,201 @@
 use super::*;
+use crate::cloud::ipc::{self, IpcRequest};
+use crate::extended_transformer::ExtendedTransformer;
+use crate::sampler::Sampler;
+use crate::tokenizer::Tokenizer;
 use std::time::Duration;
 use anyhow::Result;
+use tokio::sync::RwLock;
 
-#[derive(Debug, Clone)]
+/// Drives generation on `config.model` so `CloudManager::generate`'s
+/// `fallback_to_local` branch has somewhere real to land once every cloud
+/// provider has failed. When `config.base_url` uses the `ipc:` scheme,
+/// requests are forwarded to a co-located inference worker over a
+/// length-prefixed IPC transport (`crate::cloud::ipc`) instead; otherwise
+/// generation runs in-process, with the transformer loaded lazily on first
+/// use and kept warm across requests, the same way `AppState::LoadedModel`
+/// keeps a model resident in the API server.
 pub struct LocalProvider {
     pub config: CloudProviderConfig,
+    transformer: RwLock<Option<ExtendedTransformer>>,
+}
+
+impl std::fmt::Debug for LocalProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LocalProvider")
+            .field("config", &self.config)
+            .finish()
+    }
 }
 
 impl LocalProvider {
     pub fn new(config: CloudProviderConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            transformer: RwLock::new(None),
+        }
     }
 }
 
+/// Samples this process's resident memory once per generated token and
+/// feeds it to `controller.tick`, unlike `run_inference`'s one-shot
+/// pre-generation guard: ticking once per token lets
+/// `DynamicQuantController`'s hysteresis actually accumulate consecutive
+/// over/under samples within a single response. Already-loaded weights
+/// can't be re-quantized live, so a recommended downgrade is acted on by
+/// shrinking `effective_seq_len` instead — pulling the context ceiling in
+/// towards the current position, the same sliding-window mitigation
+/// `ContextManagement::Sliding` applies, so a response under sustained
+/// memory pressure wraps up sooner rather than exhausting the model's
+/// full `seq_len`. Returns the (possibly shrunk) ceiling to use from here
+/// on; never returns a ceiling below `pos` or above the one passed in.
+fn apply_dynamic_quant_guard(
+    controller: &mut crate::quantization::DynamicQuantController,
+    pos: usize,
+    effective_seq_len: usize,
+) -> usize {
+    let Some(usage_mb) = crate::quantization::current_rss_mb() else {
+        return effective_seq_len;
+    };
+    let Some(new_level) = controller.tick(usage_mb) else {
+        return effective_seq_len;
+    };
+    log::warn!(
+        "Dynamic quantization guard recommends {new_level} (already-loaded weights for \
+         this response are not re-quantized live); shrinking remaining context instead"
+    );
+    let remaining = effective_seq_len.saturating_sub(pos);
+    let shrunk_remaining = (remaining / 2).max(1);
+    effective_seq_len.min(pos + shrunk_remaining)
+}
+
 #[async_trait]
 impl CloudProvider for LocalProvider {
-    async fn generate(&self, _prompt: &str, _config: &InferenceConfig
-    ) -> Result<String> {
-        // This is a placeholder for local inference
-        // In a real implementation, this would use the local Qwen3 model
-        Err(anyhow::anyhow!("Local inference not implemented in cloud provider"))
+    async fn generate(&self, prompt: &str, config: &InferenceConfig) -> Result<String, CloudError> {
+        if let Some(path) = ipc::ipc_path(&self.config.base_url) {
+            return ipc::call(
+                path,
+                IpcRequest::Generate {
+                    prompt: prompt.to_string(),
+                    max_tokens: config.max_tokens,
+                    temperature: config.temperature,
+                    top_p: config.top_p,
+                    frequency_penalty: config.frequency_penalty,
+                    presence_penalty: config.presence_penalty,
+                },
+            )
+            .await
+            .map_err(|err| CloudError::Transient {
+                message: format!("IPC worker at {path} failed: {err}"),
+                retry_after: None,
+            });
+        }
+
+        let mut slot = self.transformer.write().await;
+        if slot.is_none() {
+            let loaded = ExtendedTransformer::new(&self.config.model).map_err(|err| {
+                CloudError::Permanent {
+                    message: format!(
+                        "failed to load local fallback model '{}': {err}",
+                        self.config.model
+                    ),
+                }
+            })?;
+            *slot = Some(loaded);
+        }
+        let extended = slot.as_mut().expect("just loaded above if empty");
+        let transformer = extended.transformer_mut();
+
+        let tokenizer = Tokenizer::new(&self.config.model, transformer.config.vocab_size, false)
+            .map_err(|err| CloudError::Permanent {
+                message: err.to_string(),
+            })?;
+
+        let prompt_tokens = tokenizer.encode(prompt);
+        if prompt_tokens.is_empty() {
+            return Err(CloudError::Permanent {
+                message: "Empty prompt".to_string(),
+            });
+        }
+
+        let mut sampler = Sampler::new(
+            transformer.config.vocab_size,
+            config.temperature,
+            config.top_p,
+            42, // seed
+        );
+
+        let seq_len = transformer.config.seq_len;
+        let mut response_tokens = Vec::new();
+        let mut token = prompt_tokens[0];
+        let mut pos = 0;
+
+        for &next_token in &prompt_tokens[1..] {
+            if pos >= seq_len {
+                break;
+            }
+            let _ = transformer.forward(token, pos);
+            token = next_token;
+            pos += 1;
+        }
+
+        // `effective_seq_len` is the context ceiling actually enforced below;
+        // it starts at the model's full `seq_len` and is only ever shrunk,
+        // never grown, by `apply_dynamic_quant_guard` below.
+        let mut effective_seq_len = seq_len;
+        let mut quant_guard = self.config.dynamic_quant_guard.map(|downgrade_ratio| {
+            let cpu_target = crate::quantization::CpuTarget::detect();
+            let max_memory_mb =
+                crate::quantization::MemoryLimits::for_cpu_target(cpu_target).max_memory_mb;
+            crate::quantization::DynamicQuantController::new(
+                cpu_target.optimal_quantization(),
+                cpu_target,
+                max_memory_mb,
+                downgrade_ratio,
+            )
+        });
+
+        while response_tokens.len() < config.max_tokens && pos < effective_seq_len {
+            let logits = transformer.forward(token, pos);
+            let mut logits_copy = logits.to_vec();
+            let next_token = sampler.sample(&mut logits_copy);
+
+            if next_token == tokenizer.eos_token_id as usize
+                || next_token == tokenizer.bos_token_id as usize
+            {
+                break;
+            }
+
+            response_tokens.push(next_token);
+            token = next_token;
+            pos += 1;
+
+            if let Some(controller) = quant_guard.as_mut() {
+                effective_seq_len =
+                    apply_dynamic_quant_guard(controller, pos, effective_seq_len);
+            }
+        }
+
+        Ok(response_tokens
+            .iter()
+            .map(|&token| tokenizer.decode(token))
+            .collect())
     }
 
     async fn check_health(&self
     ) -> HealthStatus {
+        let healthy = match ipc::ipc_path(&self.config.base_url) {
+            Some(path) => ipc::call(path, IpcRequest::Ping).await.is_ok(),
+            // No IPC worker configured: generation runs in-process, so
+            // there's nothing external to probe.
+            None => true,
+        };
+
         HealthStatus {
-            healthy: true,
+            healthy,
             latency: Duration::from_millis(0),
             last_check: std::time::SystemTime::now(),
-            error_count: 0,
+            error_count: if healthy { 0 } else { 1 },
         }
     }
 
@@ -45,6 +212,10 @@ impl CloudProvider for LocalProvider {
     ) -> &str {
         &self.config.name
     }
+
+    fn retries(&self) -> u8 {
+        self.config.retries
+    }
 }
 
 #[cfg(test)]
@@ -61,6 +232,8 @@ mod tests {
             max_tokens: 512,
             temperature: 0.7,
             timeout: Duration::from_secs(30),
+            retries: 2,
+            dynamic_quant_guard: None,
         };
         
         let provider = LocalProvider::new(config);
@@ -79,6 +252,8 @@ mod tests {
             max_tokens: 512,
             temperature: 0.7,
             timeout: Duration::from_secs(30),
+            retries: 2,
+            dynamic_quant_guard: None,
         };
         
         let provider = LocalProvider::new(config);
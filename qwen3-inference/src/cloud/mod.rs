@@ -15,6 +15,51 @@ pub struct CloudConfig {
     pub providers: Vec<CloudProviderConfig>,
     pub fallback_to_local: bool,
     pub health_check_interval: u64,
+    /// How `CloudManager::generate` orders the healthy providers it falls
+    /// back to once `preferred_provider` has been tried (or wasn't given).
+    #[serde(default)]
+    pub routing_strategy: RoutingStrategy,
+    /// Ceiling on the exponential backoff the background health-check loop
+    /// applies to a provider with consecutive failures.
+    #[serde(default = "default_health_check_backoff_cap_secs")]
+    pub health_check_backoff_cap_secs: u64,
+    /// Delay between successive per-provider probes within a single health
+    /// check round, so all providers aren't hit at once.
+    #[serde(default = "default_health_check_pacing_ms")]
+    pub health_check_pacing_ms: u64,
+    /// Checkpoint path for the in-process model `CloudManager::generate`
+    /// runs through `LocalProvider`/`ExtendedTransformer` once every cloud
+    /// provider has failed. Ignored unless `fallback_to_local` is set;
+    /// leaving it unset disables the fallback even if the flag is on.
+    #[serde(default)]
+    pub local_fallback_model_path: Option<String>,
+    /// Opt-in memory-pressure downgrade ratio forwarded to the local
+    /// fallback's `LocalProvider::dynamic_quant_guard`. Ignored unless
+    /// `local_fallback_model_path` is also set.
+    #[serde(default)]
+    pub local_fallback_dynamic_quant_guard: Option<f32>,
+}
+
+fn default_health_check_backoff_cap_secs() -> u64 {
+    300
+}
+
+fn default_health_check_pacing_ms() -> u64 {
+    250
+}
+
+/// Scores candidate providers for fallback ordering in
+/// [`CloudManager::get_routing_plan`]. Lower score is tried first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum RoutingStrategy {
+    /// Score purely by `get_cost_estimate(max_tokens)`.
+    CheapestFirst,
+    /// Score purely by `get_latency_estimate()`.
+    LowestLatency,
+    /// Score by cost and latency combined, so a cheap-but-slow provider
+    /// and a fast-but-expensive one don't automatically dominate.
+    #[default]
+    Balanced,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -27,6 +72,97 @@ pub struct CloudProviderConfig {
     pub temperature: f32,
     #[serde(with = "humantime_serde")]
     pub timeout: Duration,
+    /// Extra attempts (beyond the first) for failures `CloudError` marks
+    /// retryable. `0` means fail on the first transient error.
+    #[serde(default)]
+    pub retries: u8,
+    /// Opt-in memory-pressure downgrade ratio for `LocalProvider`'s
+    /// in-process fallback generation (the `downgrade_ratio` passed to
+    /// `DynamicQuantController::new`). Ignored by HTTP-backed providers.
+    #[serde(default)]
+    pub dynamic_quant_guard: Option<f32>,
+}
+
+/// Distinguishes failures a retry can plausibly fix (HTTP 429/5xx, request
+/// timeouts) from ones it can't (4xx auth/validation failures), so the
+/// retry loop in [`CloudManager::generate`] knows when to keep trying
+/// versus fail fast and surface the provider's own error body.
+#[derive(Debug)]
+pub enum CloudError {
+    /// Worth retrying, optionally after the provider's own `Retry-After`.
+    Transient {
+        message: String,
+        retry_after: Option<Duration>,
+    },
+    /// Retrying won't help (bad API key, malformed request, etc.).
+    Permanent { message: String },
+}
+
+impl CloudError {
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, CloudError::Transient { .. })
+    }
+
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            CloudError::Transient { retry_after, .. } => *retry_after,
+            CloudError::Permanent { .. } => None,
+        }
+    }
+}
+
+impl std::fmt::Display for CloudError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CloudError::Transient { message, .. } => write!(f, "{message}"),
+            CloudError::Permanent { message } => write!(f, "{message}"),
+        }
+    }
+}
+
+impl std::error::Error for CloudError {}
+
+/// Classifies a non-success HTTP response from a cloud provider, pulling
+/// `Retry-After` out when present and folding the response body into the
+/// message so auth/validation failures aren't swallowed as a bare status
+/// code.
+pub(crate) async fn classify_http_error(
+    provider_name: &str,
+    response: reqwest::Response,
+) -> CloudError {
+    let status = response.status();
+    let retry_after = response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<u64>().ok())
+        .map(Duration::from_secs);
+    let body = response.text().await.unwrap_or_default();
+    let message = format!("{provider_name} API error: {status} - {body}");
+
+    if status == reqwest::StatusCode::TOO_MANY_REQUESTS || status.is_server_error() {
+        CloudError::Transient {
+            message,
+            retry_after,
+        }
+    } else {
+        CloudError::Permanent { message }
+    }
+}
+
+/// Classifies a transport-level `reqwest` failure (as opposed to a
+/// non-success HTTP status): timeouts and connection failures are worth
+/// retrying, anything else (e.g. a malformed request body) is not.
+pub(crate) fn classify_transport_error(err: reqwest::Error) -> CloudError {
+    let message = err.to_string();
+    if err.is_timeout() || err.is_connect() {
+        CloudError::Transient {
+            message,
+            retry_after: None,
+        }
+    } else {
+        CloudError::Permanent { message }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -48,44 +184,271 @@ pub struct HealthStatus {
 
 #[async_trait]
 pub trait CloudProvider: Send + Sync {
-    async fn generate(&self, prompt: &str, config: &InferenceConfig) -> Result<String>;
+    async fn generate(&self, prompt: &str, config: &InferenceConfig) -> Result<String, CloudError>;
     async fn check_health(&self) -> HealthStatus;
     fn get_cost_estimate(&self, tokens: usize) -> f64;
     fn get_latency_estimate(&self) -> Duration;
     fn get_name(&self) -> &str;
+    /// Extra attempts (beyond the first) the retry loop should spend on
+    /// this provider before giving up on a transient failure.
+    fn retries(&self) -> u8;
 }
 
+/// Default API base URL for a provider name, used when
+/// `quantization::CloudConfig.base_url` is unset.
+fn default_base_url(provider: &str) -> String {
+    match provider {
+        "anthropic" => "https://api.anthropic.com/v1".to_string(),
+        _ => "https://api.openai.com/v1".to_string(),
+    }
+}
+
+impl From<&crate::quantization::CloudConfig> for CloudProviderConfig {
+    fn from(config: &crate::quantization::CloudConfig) -> Self {
+        Self {
+            name: config.provider.clone(),
+            api_key: Some(config.api_key.clone()),
+            base_url: config
+                .base_url
+                .clone()
+                .unwrap_or_else(|| default_base_url(&config.provider)),
+            model: config.model_name.clone(),
+            max_tokens: config.max_tokens,
+            temperature: 0.7,
+            timeout: Duration::from_secs(config.timeout_seconds),
+            retries: config.retries,
+            dynamic_quant_guard: None,
+        }
+    }
+}
+
+/// Builds a `reqwest::Client` with pooled keep-alive connections, shared by
+/// every HTTP-backed provider a `CloudManager` owns instead of each
+/// standing up its own connection pool and TLS setup. `timeout` is only a
+/// connect-phase ceiling here — individual requests still apply their own
+/// provider-configured timeout via `RequestBuilder::timeout`, since
+/// providers in the same pool can be configured with different timeouts.
+pub fn build_http_client(connect_timeout: Duration) -> Result<reqwest::Client> {
+    Ok(reqwest::Client::builder()
+        .connect_timeout(connect_timeout)
+        .pool_idle_timeout(Duration::from_secs(90))
+        .tcp_keepalive(Duration::from_secs(60))
+        .build()?)
+}
+
+/// Builds a single provider from its config and a `reqwest::Client` to
+/// share, matching on `name` the same way [`CloudManager::new`] does for
+/// its provider list. Lets callers that only need one provider (e.g. the
+/// single `quantization::CloudConfig` attached to `ExtendedModelConfig`)
+/// skip standing up a whole `CloudManager`.
+pub fn build_provider(
+    config: CloudProviderConfig,
+    client: reqwest::Client,
+) -> Result<Arc<dyn CloudProvider>> {
+    let provider: Arc<dyn CloudProvider> = match config.name.as_str() {
+        "openai" => Arc::new(OpenAiProvider::new(config, client)),
+        "anthropic" => Arc::new(AnthropicProvider::new(config, client)),
+        "local" => Arc::new(LocalProvider::new(config)),
+        name => return Err(anyhow::anyhow!("Unknown cloud provider: {}", name)),
+    };
+    Ok(provider)
+}
+
+/// Runs `provider.generate` with exponential backoff, retrying only
+/// `CloudError::Transient` failures up to `provider.retries()` extra times
+/// and honoring the provider's own `Retry-After` when it sent one.
+/// Permanent failures (bad API key, malformed request) return immediately
+/// with the provider's own error message intact.
+pub async fn generate_with_retries(
+    provider: &Arc<dyn CloudProvider>,
+    prompt: &str,
+    config: &InferenceConfig,
+) -> Result<String, CloudError> {
+    let max_attempts = provider.retries().saturating_add(1);
+    let mut last_err = None;
+
+    for attempt in 0..max_attempts {
+        match provider.generate(prompt, config).await {
+            Ok(response) => return Ok(response),
+            Err(err) => {
+                if !err.is_retryable() || attempt + 1 == max_attempts {
+                    return Err(err);
+                }
+                let backoff = err
+                    .retry_after()
+                    .unwrap_or_else(|| Duration::from_millis(200 * 2u64.pow(attempt as u32)));
+                log::warn!(
+                    "Provider {} attempt {}/{} failed: {}; retrying in {:?}",
+                    provider.get_name(),
+                    attempt + 1,
+                    max_attempts,
+                    err,
+                    backoff
+                );
+                tokio::time::sleep(backoff).await;
+                last_err = Some(err);
+            }
+        }
+    }
+
+    Err(last_err.unwrap_or(CloudError::Permanent {
+        message: "Exhausted retries with no recorded error".to_string(),
+    }))
+}
+
+/// Spawns the background task that keeps `health_cache` fresh so
+/// `get_healthy_providers`/`get_routing_plan` don't have to default
+/// everything to "healthy" between explicit `check_all_health` calls.
+/// Providers are probed in a round-robin cycle with a `pacing` delay
+/// between each one (so they aren't all hit in the same instant), and a
+/// provider due for a probe is skipped until its own `next_probe_at`,
+/// which backs off exponentially (capped at `backoff_cap`) on consecutive
+/// failures and trips unhealthy once `CIRCUIT_BREAKER_THRESHOLD` failures
+/// in a row are reached.
+fn spawn_health_check_loop(
+    providers: Arc<HashMap<String, Arc<dyn CloudProvider>>>,
+    health_cache: Arc<RwLock<HashMap<String, HealthStatus>>>,
+    base_interval: Duration,
+    backoff_cap: Duration,
+    pacing: Duration,
+) -> tokio::task::AbortHandle {
+    let task = tokio::spawn(async move {
+        let mut circuit_state: HashMap<String, CircuitState> = HashMap::new();
+
+        loop {
+            for (name, provider) in providers.iter() {
+                let now = std::time::Instant::now();
+                let due = circuit_state
+                    .get(name)
+                    .map(|state| now >= state.next_probe_at)
+                    .unwrap_or(true);
+
+                if due {
+                    let mut status = provider.check_health().await;
+                    let state = circuit_state.entry(name.clone()).or_insert(CircuitState {
+                        consecutive_failures: 0,
+                        next_probe_at: now,
+                    });
+
+                    if status.healthy {
+                        state.consecutive_failures = 0;
+                        state.next_probe_at = now + base_interval;
+                    } else {
+                        state.consecutive_failures = state.consecutive_failures.saturating_add(1);
+                        let backoff = base_interval
+                            .saturating_mul(1u32 << state.consecutive_failures.min(6))
+                            .min(backoff_cap);
+                        state.next_probe_at = now + backoff;
+                    }
+
+                    status.error_count = state.consecutive_failures as usize;
+                    status.healthy = state.consecutive_failures < CIRCUIT_BREAKER_THRESHOLD;
+
+                    health_cache.write().await.insert(name.clone(), status);
+                }
+
+                tokio::time::sleep(pacing).await;
+            }
+
+            if providers.is_empty() {
+                tokio::time::sleep(base_interval).await;
+            }
+        }
+    });
+
+    task.abort_handle()
+}
+
+/// Per-provider circuit-breaker bookkeeping for the background health-check
+/// loop. `consecutive_failures` drives the exponential backoff applied to
+/// `next_probe_at`, and trips the provider unhealthy once it reaches
+/// `CIRCUIT_BREAKER_THRESHOLD`; a passing probe resets it to zero.
+#[derive(Debug, Clone)]
+struct CircuitState {
+    consecutive_failures: u32,
+    next_probe_at: std::time::Instant,
+}
+
+/// Consecutive failed probes after which a provider is forced unhealthy
+/// regardless of backoff state, so a single flaky probe doesn't flip it.
+const CIRCUIT_BREAKER_THRESHOLD: u32 = 3;
+
 /// Cloud provider manager for hybrid inference
 pub struct CloudManager {
-    providers: HashMap<String, Arc<dyn CloudProvider>>,
+    providers: Arc<HashMap<String, Arc<dyn CloudProvider>>>,
     health_cache: Arc<RwLock<HashMap<String, HealthStatus>>>,
     fallback_to_local: bool,
     health_check_interval: Duration,
+    routing_strategy: RoutingStrategy,
+    /// In-process provider `generate` falls back to once every routed cloud
+    /// provider has failed. `None` if no `local_fallback_model_path` was
+    /// configured; only consulted when `fallback_to_local` is set.
+    local_provider: Option<Arc<dyn CloudProvider>>,
+    /// Aborts the background health-check loop when the manager is dropped.
+    health_check_handle: tokio::task::AbortHandle,
+}
+
+impl Drop for CloudManager {
+    fn drop(&mut self) {
+        self.health_check_handle.abort();
+    }
 }
 
 impl CloudManager {
     pub fn new(config: CloudConfig) -> Result<Self> {
         let mut providers = HashMap::new();
-        
-        // Add providers from configuration
+        let http_client = build_http_client(Duration::from_secs(10))?;
+
+        // Add providers from configuration, all sharing one pooled client.
         for provider_config in config.providers {
-            let provider: Arc<dyn CloudProvider> = match provider_config.name.as_str() {
-                "openai" => Arc::new(OpenAiProvider::new(provider_config.clone())),
-                "anthropic" => Arc::new(AnthropicProvider::new(provider_config.clone())),
-                "local" => Arc::new(LocalProvider::new(provider_config.clone())),
-                name => return Err(anyhow::anyhow!("Unknown cloud provider: {}", name)),
-            };
-            providers.insert(provider_config.name.clone(), provider);
+            let name = provider_config.name.clone();
+            providers.insert(name, build_provider(provider_config, http_client.clone())?);
         }
-        
+
+        let providers = Arc::new(providers);
+        let health_cache = Arc::new(RwLock::new(HashMap::new()));
+        let health_check_interval = Duration::from_secs(config.health_check_interval);
+        let health_check_handle = spawn_health_check_loop(
+            providers.clone(),
+            health_cache.clone(),
+            health_check_interval,
+            Duration::from_secs(config.health_check_backoff_cap_secs),
+            Duration::from_millis(config.health_check_pacing_ms),
+        );
+
+        let local_fallback_dynamic_quant_guard = config.local_fallback_dynamic_quant_guard;
+        let local_provider = config.local_fallback_model_path.map(|model_path| {
+            Arc::new(LocalProvider::new(CloudProviderConfig {
+                name: "local".to_string(),
+                api_key: None,
+                base_url: String::new(),
+                model: model_path,
+                max_tokens: 0,
+                temperature: 0.0,
+                timeout: Duration::from_secs(30),
+                retries: 0,
+                dynamic_quant_guard: local_fallback_dynamic_quant_guard,
+            })) as Arc<dyn CloudProvider>
+        });
+
         Ok(CloudManager {
             providers,
-            health_cache: Arc::new(RwLock::new(HashMap::new())),
+            health_cache,
             fallback_to_local: config.fallback_to_local,
-            health_check_interval: Duration::from_secs(config.health_check_interval),
+            health_check_interval,
+            routing_strategy: config.routing_strategy,
+            local_provider,
+            health_check_handle,
         })
     }
-    
+
+    /// Read-only snapshot of the most recently cached health status for
+    /// every provider that has been probed at least once, for the
+    /// `/health` endpoint to surface without triggering a probe itself.
+    pub async fn get_health_statuses(&self) -> HashMap<String, HealthStatus> {
+        self.health_cache.read().await.clone()
+    }
+
     pub async fn generate(
         &self,
         prompt: &str,
@@ -95,7 +458,7 @@ impl CloudManager {
         // Try preferred provider first
         if let Some(provider_name) = preferred_provider {
             if let Some(provider) = self.providers.get(provider_name) {
-                match provider.generate(prompt, config).await {
+                match generate_with_retries(provider, prompt, config).await {
                     Ok(response) => return Ok(response),
                     Err(e) => {
                         log::warn!("Provider {} failed: {}", provider_name, e);
@@ -103,12 +466,16 @@ impl CloudManager {
                 }
             }
         }
-        
-        // Try all healthy providers
-        let healthy_providers = self.get_healthy_providers().await;
-        for provider_name in healthy_providers {
+
+        // Try the remaining healthy providers ordered by estimated
+        // cost/latency per `routing_strategy`, instead of arbitrary
+        // `HashMap` iteration order.
+        for provider_name in self.get_routing_plan(prompt, config).await {
+            if preferred_provider == Some(provider_name.as_str()) {
+                continue;
+            }
             if let Some(provider) = self.providers.get(&provider_name) {
-                match provider.generate(prompt, config).await {
+                match generate_with_retries(provider, prompt, config).await {
                     Ok(response) => return Ok(response),
                     Err(e) => {
                         log::warn!("Provider {} failed: {}", provider_name, e);
@@ -116,15 +483,50 @@ impl CloudManager {
                 }
             }
         }
-        
-        // Fallback to local if enabled
+
+        // Every configured cloud provider failed (or none are healthy);
+        // run generation in-process on the configured fallback model.
         if self.fallback_to_local {
-            return Err(anyhow::anyhow!("All cloud providers failed and fallback to local not implemented"));
+            if let Some(local) = &self.local_provider {
+                return generate_with_retries(local, prompt, config)
+                    .await
+                    .map_err(|e| anyhow::anyhow!("Local fallback failed: {e}"));
+            }
+            return Err(anyhow::anyhow!(
+                "All cloud providers failed and no local_fallback_model_path is configured"
+            ));
         }
-        
+
         Err(anyhow::anyhow!("All cloud providers failed"))
     }
-    
+
+    /// Ranks the currently-healthy providers by a score combining
+    /// `get_cost_estimate(config.max_tokens)` and `get_latency_estimate()`,
+    /// per `self.routing_strategy` — lowest score first. `generate` walks
+    /// this same order for its failover loop; exposed standalone so callers
+    /// can inspect the planned fan-out before a request is actually sent.
+    pub async fn get_routing_plan(&self, _prompt: &str, config: &InferenceConfig) -> Vec<String> {
+        let healthy = self.get_healthy_providers().await;
+
+        let mut scored: Vec<(String, f64)> = healthy
+            .into_iter()
+            .filter_map(|name| {
+                let provider = self.providers.get(&name)?;
+                let cost = provider.get_cost_estimate(config.max_tokens);
+                let latency_secs = provider.get_latency_estimate().as_secs_f64();
+                let score = match self.routing_strategy {
+                    RoutingStrategy::CheapestFirst => cost,
+                    RoutingStrategy::LowestLatency => latency_secs,
+                    RoutingStrategy::Balanced => cost + latency_secs,
+                };
+                Some((name, score))
+            })
+            .collect();
+
+        scored.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+        scored.into_iter().map(|(name, _)| name).collect()
+    }
+
     pub async fn get_healthy_providers(&self) -> Vec<String> {
         let mut healthy = Vec::new();
         let health_cache = self.health_cache.read().await;
@@ -176,6 +578,7 @@ impl CloudManager {
 pub mod openai;
 pub mod anthropic;
 pub mod local;
+pub mod ipc;
 
 #[cfg(test)]
 mod tests {
@@ -192,11 +595,18 @@ mod tests {
                 max_tokens: 512,
                 temperature: 0.7,
                 timeout: Duration::from_secs(30),
+                retries: 2,
+                dynamic_quant_guard: None,
             }],
             fallback_to_local: true,
             health_check_interval: 60,
+            routing_strategy: RoutingStrategy::default(),
+            health_check_backoff_cap_secs: default_health_check_backoff_cap_secs(),
+            health_check_pacing_ms: default_health_check_pacing_ms(),
+            local_fallback_model_path: None,
+            local_fallback_dynamic_quant_guard: None,
         };
-        
+
         let manager = CloudManager::new(config).unwrap();
         assert_eq!(manager.providers.len(), 1);
     }
@@ -207,10 +617,122 @@ mod tests {
             providers: vec![],
             fallback_to_local: true,
             health_check_interval: 60,
+            routing_strategy: RoutingStrategy::default(),
+            health_check_backoff_cap_secs: default_health_check_backoff_cap_secs(),
+            health_check_pacing_ms: default_health_check_pacing_ms(),
+            local_fallback_model_path: None,
+            local_fallback_dynamic_quant_guard: None,
         };
-        
+
         let manager = CloudManager::new(config).unwrap();
         let health = manager.check_all_health().await;
         assert!(health.is_empty());
     }
+
+    fn provider_config(name: &str) -> CloudProviderConfig {
+        CloudProviderConfig {
+            name: name.to_string(),
+            api_key: Some("test-key".to_string()),
+            base_url: default_base_url(name),
+            model: "test-model".to_string(),
+            max_tokens: 512,
+            temperature: 0.7,
+            timeout: Duration::from_secs(30),
+            retries: 0,
+            dynamic_quant_guard: None,
+        }
+    }
+
+    fn inference_config() -> InferenceConfig {
+        InferenceConfig {
+            max_tokens: 1000,
+            temperature: 0.7,
+            top_p: 1.0,
+            frequency_penalty: 0.0,
+            presence_penalty: 0.0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_routing_plan_cheapest_first() {
+        // Anthropic is cheaper but slower than OpenAI at this token count.
+        let config = CloudConfig {
+            providers: vec![provider_config("openai"), provider_config("anthropic")],
+            fallback_to_local: false,
+            health_check_interval: 60,
+            routing_strategy: RoutingStrategy::CheapestFirst,
+            health_check_backoff_cap_secs: default_health_check_backoff_cap_secs(),
+            health_check_pacing_ms: default_health_check_pacing_ms(),
+            local_fallback_model_path: None,
+            local_fallback_dynamic_quant_guard: None,
+        };
+        let manager = CloudManager::new(config).unwrap();
+
+        let plan = manager.get_routing_plan("hello", &inference_config()).await;
+        assert_eq!(plan, vec!["anthropic".to_string(), "openai".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_routing_plan_lowest_latency() {
+        // OpenAI is faster but pricier than Anthropic at this token count.
+        let config = CloudConfig {
+            providers: vec![provider_config("openai"), provider_config("anthropic")],
+            fallback_to_local: false,
+            health_check_interval: 60,
+            routing_strategy: RoutingStrategy::LowestLatency,
+            health_check_backoff_cap_secs: default_health_check_backoff_cap_secs(),
+            health_check_pacing_ms: default_health_check_pacing_ms(),
+            local_fallback_model_path: None,
+            local_fallback_dynamic_quant_guard: None,
+        };
+        let manager = CloudManager::new(config).unwrap();
+
+        let plan = manager.get_routing_plan("hello", &inference_config()).await;
+        assert_eq!(plan, vec!["openai".to_string(), "anthropic".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_generate_without_local_fallback_configured_errors() {
+        let config = CloudConfig {
+            providers: vec![],
+            fallback_to_local: true,
+            health_check_interval: 60,
+            routing_strategy: RoutingStrategy::default(),
+            health_check_backoff_cap_secs: default_health_check_backoff_cap_secs(),
+            health_check_pacing_ms: default_health_check_pacing_ms(),
+            local_fallback_model_path: None,
+            local_fallback_dynamic_quant_guard: None,
+        };
+        let manager = CloudManager::new(config).unwrap();
+
+        let err = manager
+            .generate("hello", &inference_config(), None)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("no local_fallback_model_path"));
+    }
+
+    #[tokio::test]
+    async fn test_generate_falls_back_to_local_provider() {
+        // No checkpoint actually exists at this path, so the fallback is
+        // expected to surface `LocalProvider`'s load failure rather than
+        // the old hardcoded "not implemented" error.
+        let config = CloudConfig {
+            providers: vec![],
+            fallback_to_local: true,
+            health_check_interval: 60,
+            routing_strategy: RoutingStrategy::default(),
+            health_check_backoff_cap_secs: default_health_check_backoff_cap_secs(),
+            health_check_pacing_ms: default_health_check_pacing_ms(),
+            local_fallback_model_path: Some("/nonexistent/model.bin".to_string()),
+            local_fallback_dynamic_quant_guard: None,
+        };
+        let manager = CloudManager::new(config).unwrap();
+
+        let err = manager
+            .generate("hello", &inference_config(), None)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("failed to load local fallback model"));
+    }
 }
\ No newline at end of file
@@ -49,19 +49,17 @@ struct OpenAiUsage {
 }
 
 impl OpenAiProvider {
-    pub fn new(config: CloudProviderConfig) -> Self {
-        let client = reqwest::Client::builder()
-            .timeout(config.timeout)
-            .build()
-            .unwrap();
-
+    /// `client` is expected to be shared across providers by the caller
+    /// (see [`super::build_http_client`]) rather than built fresh per
+    /// provider; per-request timeouts still come from `config.timeout`.
+    pub fn new(config: CloudProviderConfig, client: reqwest::Client) -> Self {
         Self { config, client }
     }
 
     pub fn from_env() -> Option<Self> {
         let api_key = env::var("OPENAI_API_KEY").ok()?;
         let base_url = env::var("OPENAI_BASE_URL").unwrap_or_else(|_| "https://api.openai.com/v1".to_string());
-        
+
         let config = CloudProviderConfig {
             name: "openai".to_string(),
             api_key: Some(api_key),
@@ -70,18 +68,22 @@ impl OpenAiProvider {
             max_tokens: 512,
             temperature: 0.7,
             timeout: Duration::from_secs(30),
+            retries: 2,
+            dynamic_quant_guard: None,
         };
 
-        Some(Self::new(config))
+        let client = super::build_http_client(config.timeout).ok()?;
+        Some(Self::new(config, client))
     }
 }
 
 #[async_trait]
 impl CloudProvider for OpenAiProvider {
     async fn generate(&self, prompt: &str, config: &InferenceConfig
-    ) -> Result<String> {
-        let api_key = self.config.api_key.as_ref()
-            .ok_or_else(|| anyhow::anyhow!("OpenAI API key not configured"))?;
+    ) -> Result<String, CloudError> {
+        let api_key = self.config.api_key.as_ref().ok_or_else(|| CloudError::Permanent {
+            message: "OpenAI API key not configured".to_string(),
+        })?;
 
         let request = OpenAiRequest {
             model: self.config.model.clone(),
@@ -101,19 +103,24 @@ impl CloudProvider for OpenAiProvider {
             .post(format!("{}/chat/completions", self.config.base_url))
             .header("Authorization", format!("Bearer {}", api_key))
             .header("Content-Type", "application/json")
+            .timeout(self.config.timeout)
             .json(&request)
             .send()
-            .await?;
+            .await
+            .map_err(classify_transport_error)?;
 
         if !response.status().is_success() {
-            return Err(anyhow::anyhow!("OpenAI API error: {}", response.status()));
+            return Err(classify_http_error("OpenAI", response).await);
         }
 
-        let response_data: OpenAiResponse = response.json().await?;
+        let response_data: OpenAiResponse =
+            response.json().await.map_err(classify_transport_error)?;
         response_data.choices
             .first()
             .map(|choice| choice.message.content.clone())
-            .ok_or_else(|| anyhow::anyhow!("No response from OpenAI"))
+            .ok_or_else(|| CloudError::Permanent {
+                message: "No response from OpenAI".to_string(),
+            })
     }
 
     async fn check_health(&self
@@ -122,8 +129,9 @@ impl CloudProvider for OpenAiProvider {
         
         let health_status = match self.client
             .get(format!("{}/models", self.config.base_url))
-            .header("Authorization", format!("Bearer {}", 
+            .header("Authorization", format!("Bearer {}",
                 self.config.api_key.as_ref().unwrap_or(&"dummy".to_string())))
+            .timeout(self.config.timeout)
             .send()
             .await
         {
@@ -165,4 +173,8 @@ impl CloudProvider for OpenAiProvider {
     ) -> &str {
         &self.config.name
     }
+
+    fn retries(&self) -> u8 {
+        self.config.retries
+    }
 }
\ No newline at end of file
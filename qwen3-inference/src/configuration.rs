@@ -7,14 +7,70 @@ use serde::{Deserialize, Serialize};
 
 /// Magic number for validating checkpoint files (matches C engine "QWEN")
 const CHECKPOINT_MAGIC: i32 = 0x5157454E;
-/// Expected checkpoint version
+/// Expected checkpoint version for the original fixed-layout header
 const CHECKPOINT_VERSION: i32 = 1;
+/// Version marking a length-prefixed `bincode`-encoded [`ModelMetadata`] blob
+const CONTAINER_VERSION_BINCODE: i32 = 2;
+/// Version marking a length-prefixed MessagePack-encoded [`ModelMetadata`] blob
+const CONTAINER_VERSION_MSGPACK: i32 = 3;
 /// Size of the checkpoint header in bytes
 const HEADER_SIZE: usize = 256;
 /// Size of config structure in bytes (old format: 8 parameters without rope_theta, new format: 9 parameters with rope_theta)
 const CONFIG_SIZE: usize = 36; // New format: 8*u32 + 1*f32 = 36 bytes
 const OLD_CONFIG_SIZE: usize = 32; // Old format: 8*u32 = 32 bytes
 
+/// Serde encoding used for a container's metadata section. `Raw` keeps the
+/// original fixed-layout 256-byte header (version 1); the other two both
+/// write a length-prefixed serde blob immediately after magic+version, so
+/// new fields can be added to [`ModelMetadata`] later without breaking
+/// readers of older files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContainerFormat {
+    Raw,
+    Bincode,
+    Msgpack,
+}
+
+impl ContainerFormat {
+    fn version(self) -> i32 {
+        match self {
+            ContainerFormat::Raw => CHECKPOINT_VERSION,
+            ContainerFormat::Bincode => CONTAINER_VERSION_BINCODE,
+            ContainerFormat::Msgpack => CONTAINER_VERSION_MSGPACK,
+        }
+    }
+}
+
+impl std::str::FromStr for ContainerFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "raw" => Ok(ContainerFormat::Raw),
+            "bincode" => Ok(ContainerFormat::Bincode),
+            "msgpack" => Ok(ContainerFormat::Msgpack),
+            other => anyhow::bail!("Unknown container format '{other}', expected raw|bincode|msgpack"),
+        }
+    }
+}
+
+/// Self-describing model metadata written by `export --container bincode|msgpack`
+/// in place of the old fixed-layout header. Unlike the raw format, the
+/// quantization level and tokenizer identity are read directly from the
+/// file rather than guessed from its name, and new fields can be appended
+/// here without invalidating files written by an older exporter.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelMetadata {
+    pub config: ModelConfig,
+    /// e.g. "int4", "int8", "fp16" — the quantization actually baked into
+    /// the weight section, as opposed to inferred from the filename.
+    pub quantization: String,
+    /// Hash of the tokenizer this checkpoint was exported with, so a
+    /// mismatched tokenizer.json can be detected at load time instead of
+    /// silently producing garbage tokens.
+    pub tokenizer_hash: String,
+}
+
 /// Configuration struct for transformer models.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModelConfig {
@@ -77,6 +133,22 @@ impl TryInto<ModelConfig> for Config {
     /// Supports both old format (32 bytes) and new format (36 bytes with rope_theta)
     /// This function performs bounds checking and validates the magic number and version.
     pub fn read_config(mapper: &mut MemoryMapper) -> Result<ModelConfig> {
+        // Peek the version without consuming it, so a metadata-container
+        // file (written by `export --container bincode|msgpack`) can be
+        // routed to `read_model_metadata` before any of the fixed-layout
+        // parsing below runs.
+        let version_bytes = mapper
+            .as_bytes()
+            .get(4..8)
+            .context("Checkpoint file too short to contain a header")?;
+        let version = Cursor::new(version_bytes)
+            .read_i32::<LittleEndian>()
+            .with_context(|| "Failed to peek checkpoint version")?;
+
+        if version == CONTAINER_VERSION_BINCODE || version == CONTAINER_VERSION_MSGPACK {
+            return Ok(read_model_metadata(mapper)?.config);
+        }
+
         // Read the entire configuration block
         let config_data = match mapper.get_bytes(CONFIG_SIZE) {
             Ok(data) => data,
@@ -146,6 +218,76 @@ impl TryInto<ModelConfig> for Config {
         config.try_into()
     }
 
+/// Reads a length-prefixed serde metadata section written after magic+version
+/// by `export --container bincode|msgpack`: `[magic:i32][version:i32][len:u32][payload]`.
+/// The encoding used for `payload` is selected by `version` rather than a
+/// separate flag, so adding a metadata field later only requires a new
+/// `ModelMetadata` field with a serde default, not a new container version.
+pub fn read_model_metadata(mapper: &mut MemoryMapper) -> Result<ModelMetadata> {
+    let header = mapper
+        .get_bytes(8)
+        .with_context(|| "Failed to read container magic/version")?;
+    let mut cursor = Cursor::new(header);
+    let magic_number = cursor
+        .read_i32::<LittleEndian>()
+        .with_context(|| "Failed to read magic number")?;
+    let version = cursor
+        .read_i32::<LittleEndian>()
+        .with_context(|| "Failed to read version")?;
+
+    if magic_number != CHECKPOINT_MAGIC {
+        anyhow::bail!(
+            "Invalid checkpoint magic number: expected {:#x}, got {:#x}",
+            CHECKPOINT_MAGIC,
+            magic_number
+        );
+    }
+
+    let len = Cursor::new(
+        mapper
+            .get_bytes(4)
+            .with_context(|| "Failed to read metadata section length")?,
+    )
+    .read_u32::<LittleEndian>()
+    .with_context(|| "Failed to parse metadata section length")? as usize;
+
+    let payload = mapper
+        .get_bytes(len)
+        .with_context(|| "Metadata section length extends past end of file")?;
+
+    match version {
+        CONTAINER_VERSION_BINCODE => bincode::deserialize(payload)
+            .with_context(|| "Failed to decode bincode model metadata"),
+        CONTAINER_VERSION_MSGPACK => rmp_serde::from_slice(payload)
+            .with_context(|| "Failed to decode MessagePack model metadata"),
+        other => anyhow::bail!("Unsupported metadata container version: {other}"),
+    }
+}
+
+/// Reads the optional rkyv-archived tensor table directly at `mapper`'s
+/// current cursor (i.e. right where [`read_config`] leaves it, just after
+/// the 256-byte header). Every tensor's offset/length is validated against
+/// the mapped file before any `&Archived<Tensor>` is handed to the caller,
+/// so weight loading stays near-instant (no deserialization, just a
+/// validated cast) while still rejecting a truncated or corrupt file with a
+/// descriptive error instead of undefined behavior.
+///
+/// This is exercised only by `qwen3-cli`'s checkpoint-inspection diagnostic,
+/// not by the real model-loading pipeline — see
+/// [`crate::weight_table::read_weight_table`] for why that pipeline doesn't
+/// exist in this crate yet and this is diagnostic-only tooling, not hardened
+/// model loading.
+pub fn read_weight_table(
+    mapper: &MemoryMapper,
+) -> Result<&crate::weight_table::ArchivedWeightTable> {
+    let offset = mapper.offset();
+    let bytes = mapper.as_bytes();
+    let region = bytes
+        .get(offset..)
+        .with_context(|| format!("Weight table offset {offset} is past the end of the checkpoint file"))?;
+    crate::weight_table::read_weight_table(region, region.len() as u64)
+}
+
 /// Validates the model configuration to ensure it's supported.
 fn validate_config(config: &Config) -> Result<()> {
     match config.magic_number {
@@ -119,38 +119,106 @@ fn detect_aarch64_target() -> CpuTarget {
     CpuTarget::Generic
 }
 
+impl Default for CpuInfo {
+    /// Conservative scalar-only fallback for callers that need a `CpuInfo`
+    /// but can't tolerate `detect`'s `Result` (e.g. resolving a tuning
+    /// profile when detection has no real failure path worth propagating).
+    fn default() -> Self {
+        Self {
+            vendor: "unknown".to_string(),
+            brand: "unknown".to_string(),
+            features: Vec::new(),
+            cores: num_cpus::get(),
+            threads: num_cpus::get(),
+            cache_sizes: CacheSizes {
+                l1_data: 32,
+                l1_instruction: 32,
+                l2: 512,
+                l3: 8192,
+            },
+            architecture: detect_architecture(),
+        }
+    }
+}
+
 impl CpuInfo {
     pub fn detect() -> Result<Self> {
         let mut features = Vec::new();
-        
+
         #[cfg(target_arch = "x86_64")]
         {
             use raw_cpuid::CpuId;
             let cpuid = CpuId::new();
-            
+
+            // get_feature_info() reflects what the *running* CPU reports via
+            // CPUID, unlike `cfg!(target_feature = ..)` which only reflects
+            // how this binary was compiled — a generic build run on a wider
+            // host would otherwise silently miss AVX2/AVX-512.
             if let Some(feature_info) = cpuid.get_feature_info() {
                 if feature_info.has_sse42() {
                     features.push("sse4.2".to_string());
                 }
-                if cfg!(target_feature = "avx2") {
+                if feature_info.has_avx() {
+                    features.push("avx".to_string());
+                }
+                if feature_info.has_fma() {
+                    features.push("fma".to_string());
+                }
+            }
+
+            if let Some(extended) = cpuid.get_extended_feature_info() {
+                if extended.has_avx2() {
                     features.push("avx2".to_string());
                 }
-                if cfg!(target_feature = "avx") {
-                    features.push("avx".to_string());
+                if extended.has_avx512f() {
+                    features.push("avx512f".to_string());
+                }
+                if extended.has_avx512bw() {
+                    features.push("avx512bw".to_string());
+                }
+                if extended.has_avx512vl() {
+                    features.push("avx512vl".to_string());
+                }
+                if extended.has_bmi2() {
+                    features.push("bmi2".to_string());
                 }
             }
         }
-        
+
         #[cfg(target_arch = "aarch64")]
         {
-            if cfg!(target_feature = "neon") {
-                features.push("neon".to_string());
-            }
-            if cfg!(target_feature = "sve") {
-                features.push("sve".to_string());
+            // `/proc/cpuinfo`'s "Features" line is HWCAP-derived and
+            // reflects the actual silicon, so parse it directly rather than
+            // relying on how this binary happened to be compiled.
+            if let Ok(cpuinfo) = std::fs::read_to_string("/proc/cpuinfo") {
+                let feature_line = cpuinfo
+                    .lines()
+                    .find(|line| line.starts_with("Features"))
+                    .unwrap_or("");
+                let has_token = |token: &str| {
+                    feature_line
+                        .split_whitespace()
+                        .any(|tok| tok.eq_ignore_ascii_case(token))
+                };
+
+                if has_token("asimd") || has_token("neon") {
+                    features.push("neon".to_string());
+                }
+                if has_token("sve") {
+                    features.push("sve".to_string());
+                }
+                if has_token("sve2") {
+                    features.push("sve2".to_string());
+                }
+                if has_token("i8mm") {
+                    features.push("i8mm".to_string());
+                }
+                if has_token("bf16") {
+                    features.push("bf16".to_string());
+                }
             }
         }
-        
+
         let cores = num_cpus::get();
         let threads = num_cpus::get();
         
@@ -171,15 +239,95 @@ impl CpuInfo {
     
     pub fn get_optimization_level(&self) -> OptimizationLevel {
         match self.features.as_slice() {
-            _ if self.supports_feature("avx512f") => OptimizationLevel::Avx512,
+            // AVX-512 additionally requires OS support for the wider
+            // ZMM register state (XCR0 bits 5-7); without it, code that
+            // executes an AVX-512 instruction takes a #UD trap even though
+            // CPUID reports the feature.
+            _ if self.supports_feature("avx512f")
+                && self.supports_feature("avx512bw")
+                && avx512_usable() =>
+            {
+                OptimizationLevel::Avx512
+            }
             _ if self.supports_feature("avx2") => OptimizationLevel::Avx2,
             _ if self.supports_feature("neon") => OptimizationLevel::Neon,
             _ => OptimizationLevel::Scalar,
         }
     }
+
+    /// Picks the real codepath a single portable binary should execute on
+    /// this host, based on the live `OptimizationLevel` rather than
+    /// compile-time flags. Never promises an ISA `get_optimization_level`
+    /// hasn't actually verified is both present and usable.
+    pub fn dispatch_kernel(&self) -> OptimizationLevel {
+        self.get_optimization_level()
+    }
+}
+
+/// Checks that the OS has opted into saving the AVX-512 (ZMM/opmask)
+/// register state across context switches, via `XGETBV(0)` bits 5-7
+/// (`opmask`, `ZMM_Hi256`, `Hi16_ZMM`). CPUID can report AVX-512F present
+/// on hardware whose OS hasn't enabled that state, in which case executing
+/// an AVX-512 instruction traps.
+#[cfg(target_arch = "x86_64")]
+fn avx512_usable() -> bool {
+    if !std::is_x86_feature_detected!("xsave") {
+        return false;
+    }
+    let xcr0 = unsafe { read_xcr0() };
+    (xcr0 & 0xE0) == 0xE0
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "xsave")]
+unsafe fn read_xcr0() -> u64 {
+    unsafe { std::arch::x86_64::_xgetbv(0) }
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+fn avx512_usable() -> bool {
+    false
+}
+
+/// GEMM cache-blocking tile sizes derived from a host's detected cache
+/// hierarchy, following the classic BLIS/GotoBLAS blocking recurrence: `kc`
+/// (the K-panel width) is chosen so a `kc`-column strip of the B panel fits
+/// in L2, `mc` so an `mc`x`kc` A-panel fits in L1 data, and the innermost
+/// `mr`x`nr` register tile is sized to the widest SIMD width this CPU
+/// actually has (from [`OptimizationLevel`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheBlockingStrategy {
+    pub kc: usize,
+    pub mc: usize,
+    pub nr: usize,
+    pub mr: usize,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+impl CacheBlockingStrategy {
+    /// Computes tile sizes from `cpu`'s detected L1/L2 sizes and SIMD width,
+    /// assuming `f32` elements throughout.
+    pub fn for_cpu(cpu: &CpuInfo) -> Self {
+        let elem_size = std::mem::size_of::<f32>();
+        let l1_bytes = cpu.cache_sizes.l1_data * 1024;
+        let l2_bytes = cpu.cache_sizes.l2 * 1024;
+
+        let (mr, nr) = match cpu.get_optimization_level() {
+            OptimizationLevel::Avx512 => (16, 32),
+            OptimizationLevel::Avx2 => (8, 16),
+            OptimizationLevel::Neon => (4, 8),
+            OptimizationLevel::Scalar => (2, 4),
+        };
+
+        // A kc-column strip of B is nr*kc elements; keep it inside L2.
+        let kc = (l2_bytes / (nr * elem_size)).max(nr);
+        // An mc x kc panel of A must fit in L1 data alongside working set.
+        let mc = (l1_bytes / (kc * elem_size)).max(mr);
+
+        Self { kc, mc, nr, mr }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum OptimizationLevel {
     Scalar,
     Avx2,
@@ -198,6 +346,223 @@ impl fmt::Display for OptimizationLevel {
     }
 }
 
+/// Hand-tuned execution parameters for a [`CpuTarget`]: worker-thread
+/// count, GEMM cache-blocking tiles, the preferred SIMD codepath, and the
+/// quantization level to default to on this chip. `CpuTarget::detect`
+/// only identifies the chip; `TuningProfile` is what turns that
+/// identification into actual knobs for the transformer builder.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TuningProfile {
+    pub threads: usize,
+    pub cache_blocking: CacheBlockingStrategy,
+    pub optimization_level: OptimizationLevel,
+    pub default_quantization: crate::quantization::QuantizationLevel,
+}
+
+impl TuningProfile {
+    /// Resolves the profile for a statically-known target. `Generic` and
+    /// `Custom` both fall back to [`Self::generic`], since neither has a
+    /// hand-tuned entry here — `Custom` is instead meant to be resolved
+    /// through a [`TuningProfileRegistry`] loaded from a user's file.
+    pub fn for_target(target: &CpuTarget, cpu_info: &CpuInfo) -> Self {
+        match target {
+            CpuTarget::IntelN100 => Self::intel_n100(),
+            CpuTarget::IntelI9_14900HX => Self::intel_i9_14900hx(),
+            CpuTarget::RaspberryPi4 => Self::raspberry_pi4(),
+            CpuTarget::RaspberryPi5 => Self::raspberry_pi5(),
+            CpuTarget::AppleM1 => Self::apple_m1(),
+            CpuTarget::AppleM2 => Self::apple_m2(),
+            CpuTarget::Generic | CpuTarget::Custom(_) => Self::generic(cpu_info),
+        }
+    }
+
+    /// Derives a profile straight from live detection, for targets this
+    /// crate has no hand-tuned entry for.
+    pub fn generic(cpu_info: &CpuInfo) -> Self {
+        use crate::quantization::QuantizationLevel;
+
+        Self {
+            threads: cpu_info.cores.max(1),
+            cache_blocking: CacheBlockingStrategy::for_cpu(cpu_info),
+            optimization_level: cpu_info.get_optimization_level(),
+            default_quantization: QuantizationLevel::Int8 { group_size: 64 },
+        }
+    }
+
+    /// 4 cores/4 threads (no SMT), AVX2 only, 6MB L3 shared across the die.
+    fn intel_n100() -> Self {
+        use crate::quantization::QuantizationLevel;
+
+        Self {
+            threads: 4,
+            cache_blocking: CacheBlockingStrategy {
+                kc: 256,
+                mc: 96,
+                nr: 16,
+                mr: 8,
+            },
+            optimization_level: OptimizationLevel::Avx2,
+            default_quantization: QuantizationLevel::Int8 { group_size: 64 },
+        }
+    }
+
+    /// 24 cores/32 threads (8P+16E), AVX-512 on the P-cluster, 36MB L3.
+    fn intel_i9_14900hx() -> Self {
+        use crate::quantization::QuantizationLevel;
+
+        Self {
+            threads: 32,
+            cache_blocking: CacheBlockingStrategy {
+                kc: 1024,
+                mc: 256,
+                nr: 32,
+                mr: 16,
+            },
+            optimization_level: OptimizationLevel::Avx512,
+            default_quantization: QuantizationLevel::Fp16,
+        }
+    }
+
+    /// Cortex-A72, 4 cores/4 threads, NEON only, 1MB shared L2/no L3.
+    fn raspberry_pi4() -> Self {
+        use crate::quantization::QuantizationLevel;
+
+        Self {
+            threads: 4,
+            cache_blocking: CacheBlockingStrategy {
+                kc: 128,
+                mc: 48,
+                nr: 8,
+                mr: 4,
+            },
+            optimization_level: OptimizationLevel::Neon,
+            default_quantization: QuantizationLevel::Int4 { group_size: 32 },
+        }
+    }
+
+    /// Cortex-A76, 4 cores/4 threads, NEON only, 2MB shared L2/no L3.
+    fn raspberry_pi5() -> Self {
+        use crate::quantization::QuantizationLevel;
+
+        Self {
+            threads: 4,
+            cache_blocking: CacheBlockingStrategy {
+                kc: 192,
+                mc: 64,
+                nr: 8,
+                mr: 4,
+            },
+            optimization_level: OptimizationLevel::Neon,
+            default_quantization: QuantizationLevel::Int8 { group_size: 64 },
+        }
+    }
+
+    /// 4 performance + 4 efficiency cores, NEON-only (no SVE), 12MB shared L2.
+    fn apple_m1() -> Self {
+        use crate::quantization::QuantizationLevel;
+
+        Self {
+            threads: 8,
+            cache_blocking: CacheBlockingStrategy {
+                kc: 384,
+                mc: 128,
+                nr: 8,
+                mr: 4,
+            },
+            optimization_level: OptimizationLevel::Neon,
+            default_quantization: QuantizationLevel::Fp16,
+        }
+    }
+
+    /// 4 performance + 4 efficiency cores, NEON-only, 16MB shared L2.
+    fn apple_m2() -> Self {
+        use crate::quantization::QuantizationLevel;
+
+        Self {
+            threads: 8,
+            cache_blocking: CacheBlockingStrategy {
+                kc: 512,
+                mc: 160,
+                nr: 8,
+                mr: 4,
+            },
+            optimization_level: OptimizationLevel::Neon,
+            default_quantization: QuantizationLevel::Fp16,
+        }
+    }
+}
+
+/// User-supplied tuning profiles for `CpuTarget::Custom(name)` targets this
+/// crate has no hand-tuned entry for, loaded from a TOML or JSON file (format
+/// picked from the path's extension) referenced via
+/// [`ModelPaths::tuning_profile_path`](crate::extended_config::ModelPaths::tuning_profile_path).
+/// The file is a flat map of custom target name -> [`TuningProfile`], e.g.:
+///
+/// ```toml
+/// [my-board]
+/// threads = 6
+/// optimization_level = "Neon"
+///
+/// [my-board.cache_blocking]
+/// kc = 256
+/// mc = 96
+/// nr = 8
+/// mr = 4
+///
+/// [my-board.default_quantization.Int8]
+/// group_size = 64
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TuningProfileRegistry {
+    custom: std::collections::HashMap<String, TuningProfile>,
+}
+
+impl TuningProfileRegistry {
+    /// An empty registry: every `Custom(name)` target falls back to
+    /// [`TuningProfile::generic`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads custom profiles from `path`. JSON is used when the extension is
+    /// `.json`; anything else is parsed as TOML.
+    pub fn from_file(path: impl AsRef<std::path::Path>) -> Result<Self> {
+        use anyhow::Context;
+
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read tuning profile file: {path:?}"))?;
+
+        let custom: std::collections::HashMap<String, TuningProfile> =
+            if path.extension().and_then(|ext| ext.to_str()) == Some("json") {
+                serde_json::from_str(&content)
+                    .with_context(|| format!("Failed to parse tuning profile file: {path:?}"))?
+            } else {
+                toml::from_str(&content)
+                    .with_context(|| format!("Failed to parse tuning profile file: {path:?}"))?
+            };
+
+        Ok(Self { custom })
+    }
+
+    /// Resolves `target` to its effective profile: hand-tuned entries and
+    /// `Generic` never consult `self`, and `Custom(name)` is looked up by
+    /// name, falling back to [`TuningProfile::generic`] (with a warning) if
+    /// the file didn't have an entry for it.
+    pub fn resolve(&self, target: &CpuTarget, cpu_info: &CpuInfo) -> TuningProfile {
+        if let CpuTarget::Custom(name) = target {
+            if let Some(profile) = self.custom.get(name) {
+                return profile.clone();
+            }
+            log::warn!(
+                "No tuning profile registered for custom CPU target {name:?}; falling back to generic detection"
+            );
+            return TuningProfile::generic(cpu_info);
+        }
+        TuningProfile::for_target(target, cpu_info)
+    }
+}
+
 fn get_vendor() -> String {
     #[cfg(target_arch = "x86_64")]
     {
@@ -388,4 +753,58 @@ mod tests {
         println!("Detected CPU target: {:?}", target);
         assert!(matches!(target, CpuTarget::Generic | CpuTarget::IntelN100 | CpuTarget::IntelI9_14900HX | CpuTarget::RaspberryPi4 | CpuTarget::RaspberryPi5 | CpuTarget::AppleM1 | CpuTarget::AppleM2 | CpuTarget::Custom(_)));
     }
+
+    #[test]
+    fn test_cache_blocking_strategy_for_cpu() {
+        let cpu_info = CpuInfo::detect().unwrap();
+        let blocking = CacheBlockingStrategy::for_cpu(&cpu_info);
+        assert!(blocking.kc > 0);
+        assert!(blocking.mc > 0);
+        assert!(blocking.nr >= blocking.mr);
+        println!("Cache blocking strategy: {:?}", blocking);
+    }
+
+    #[test]
+    fn test_tuning_profile_for_known_targets_is_hand_tuned() {
+        let cpu_info = CpuInfo::default();
+        let profile = TuningProfile::for_target(&CpuTarget::RaspberryPi4, &cpu_info);
+        assert_eq!(profile.threads, 4);
+        assert_eq!(profile.optimization_level, OptimizationLevel::Neon);
+    }
+
+    #[test]
+    fn test_tuning_profile_registry_resolves_custom_target() {
+        let mut custom = std::collections::HashMap::new();
+        custom.insert(
+            "my-board".to_string(),
+            TuningProfile {
+                threads: 6,
+                cache_blocking: CacheBlockingStrategy {
+                    kc: 256,
+                    mc: 96,
+                    nr: 8,
+                    mr: 4,
+                },
+                optimization_level: OptimizationLevel::Neon,
+                default_quantization: crate::quantization::QuantizationLevel::Int8 {
+                    group_size: 64,
+                },
+            },
+        );
+        let registry = TuningProfileRegistry { custom };
+
+        let resolved = registry.resolve(
+            &CpuTarget::Custom("my-board".to_string()),
+            &CpuInfo::default(),
+        );
+        assert_eq!(resolved.threads, 6);
+
+        // An unregistered custom name falls back to generic detection
+        // instead of panicking or erroring.
+        let fallback = registry.resolve(
+            &CpuTarget::Custom("unknown-board".to_string()),
+            &CpuInfo::default(),
+        );
+        assert!(fallback.threads > 0);
+    }
 }
\ No newline at end of file
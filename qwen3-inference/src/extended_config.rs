@@ -24,8 +24,41 @@ pub struct ExtendedModelConfig {
     pub inference_params: InferenceParameters,
     /// Advanced features configuration
     pub advanced: AdvancedConfig,
+    /// Per-layer quantization assignment from the last
+    /// [`plan_mixed_precision`](Self::plan_mixed_precision) call. `None`
+    /// until that's been run at least once, in which case the uniform
+    /// `quantization` level applies to the whole model as usual.
+    #[serde(default)]
+    pub layer_quantization_plan: Option<MixedPrecisionPlan>,
 }
 
+/// One independently-quantizable parameter region of the model, as broken
+/// out by [`ExtendedModelConfig::plan_mixed_precision`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LayerKind {
+    /// Token embedding table.
+    Embedding,
+    /// Q/K/V/O projections of transformer layer `layer`.
+    Attention { layer: usize },
+    /// Gate/up/down projections of transformer layer `layer`.
+    FeedForward { layer: usize },
+    /// Output (LM head) projection, absent when `base.shared_classifier` ties it to `Embedding`.
+    OutputHead,
+}
+
+/// One entry of a [`MixedPrecisionPlan`]: which region, how many elements it
+/// holds, and the quantization level `plan_mixed_precision` chose for it.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct LayerQuantization {
+    pub kind: LayerKind,
+    pub elements: usize,
+    pub level: QuantizationLevel,
+}
+
+/// Per-layer quantization assignment produced by
+/// [`ExtendedModelConfig::plan_mixed_precision`].
+pub type MixedPrecisionPlan = Vec<LayerQuantization>;
+
 /// File paths for model components
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModelPaths {
@@ -37,6 +70,13 @@ pub struct ModelPaths {
     pub chat_template_path: Option<PathBuf>,
     /// Cache directory for temporary files
     pub cache_dir: Option<PathBuf>,
+    /// Default path for saved/restored KV-cache session snapshots
+    pub session_path: Option<PathBuf>,
+    /// TOML/JSON file of hand-tuned [`TuningProfile`](crate::cpu::TuningProfile)
+    /// entries keyed by custom CPU target name, resolved when the detected
+    /// `cpu::CpuTarget` is `Custom(name)`. `None` means only the crate's
+    /// built-in targets (and live `Generic` detection) are available.
+    pub tuning_profile_path: Option<PathBuf>,
 }
 
 /// Inference parameters with sensible defaults
@@ -90,6 +130,12 @@ pub struct AdvancedConfig {
     pub performance_monitoring: bool,
     /// Auto-save configuration changes
     pub auto_save_config: bool,
+    /// Whether [`plan_mixed_precision`](ExtendedModelConfig::plan_mixed_precision)
+    /// keeps the embedding table, output head and attention blocks at a
+    /// higher precision than feed-forward blocks for as long as possible,
+    /// only downgrading them once every feed-forward block is already at
+    /// `Int4` and the budget still isn't met.
+    pub preserve_high_precision_layers: bool,
 }
 
 /// Logging levels for the inference engine
@@ -118,6 +164,7 @@ impl ExtendedModelConfig {
             model_paths: ModelPaths::default(),
             inference_params: InferenceParameters::default(),
             advanced: AdvancedConfig::default(),
+            layer_quantization_plan: None,
         }
     }
 
@@ -135,6 +182,7 @@ impl ExtendedModelConfig {
             model_paths: ModelPaths::default(),
             inference_params: InferenceParameters::default(),
             advanced: AdvancedConfig::default(),
+            layer_quantization_plan: None,
         }
     }
 
@@ -150,6 +198,62 @@ impl ExtendedModelConfig {
         self
     }
 
+    /// Reconfigures the effective context window at runtime, e.g. to trade
+    /// context for memory on a constrained target without rebuilding the
+    /// whole config.
+    ///
+    /// Rejects `n_ctx` above `base.seq_len` (the model was never trained
+    /// with a longer window), updates `memory_limits.max_context_length`
+    /// and the active `ContextManagement` variant, and re-validates the
+    /// resulting memory footprint against `memory_limits.max_memory_mb`.
+    pub fn set_context_length(&mut self, n_ctx: usize) -> Result<()> {
+        if n_ctx == 0 {
+            anyhow::bail!("Context length must be greater than zero");
+        }
+        if n_ctx > self.base.seq_len {
+            anyhow::bail!(
+                "Requested context length ({}) exceeds the model's trained seq_len ({})",
+                n_ctx,
+                self.base.seq_len
+            );
+        }
+
+        match &mut self.inference_params.context_management {
+            ContextManagement::Fixed { max_length } => *max_length = n_ctx,
+            ContextManagement::Sliding {
+                window_size,
+                sink_size,
+            } => {
+                if *sink_size >= n_ctx {
+                    anyhow::bail!(
+                        "sink_size ({}) must be smaller than window_size ({})",
+                        sink_size,
+                        n_ctx
+                    );
+                }
+                *window_size = n_ctx;
+            }
+            ContextManagement::Dynamic { .. } => {
+                // Dynamic management derives its window from memory usage, not
+                // a fixed n_ctx; nothing to clamp here.
+            }
+        }
+
+        self.memory_limits.max_context_length = n_ctx;
+
+        let estimated_memory = self.estimate_memory_usage()?;
+        if estimated_memory > self.memory_limits.max_memory_mb {
+            anyhow::bail!(
+                "Context length {} would use an estimated {}MB, exceeding the {}MB limit",
+                n_ctx,
+                estimated_memory,
+                self.memory_limits.max_memory_mb
+            );
+        }
+
+        Ok(())
+    }
+
     /// Validates the configuration for consistency
     pub fn validate(&self) -> Result<()> {
         // Validate model paths
@@ -179,27 +283,153 @@ impl ExtendedModelConfig {
     pub fn estimate_memory_usage(&self) -> Result<usize> {
         let model_size = self.base.dim * self.base.vocab_size * 4; // Rough estimate
         let quantized_size = self.quantization.memory_usage(model_size);
-        
-        // Add overhead for KV cache, activations, etc.
-        let kv_cache_size = self.base.n_layers * self.base.seq_len * self.base.dim * 2;
-        let activation_size = self.base.seq_len * self.base.dim * 4;
-        
+
+        // Add overhead for KV cache, activations, etc. Uses the effective
+        // context length (which may be smaller than base.seq_len after
+        // set_context_length) so the estimate reflects what will actually
+        // be allocated.
+        let ctx_len = self.effective_context_length();
+        let kv_cache_size = self.base.n_layers * ctx_len * self.base.dim * 2;
+        let activation_size = ctx_len * self.base.dim * 4;
+
         let total_bytes = quantized_size + kv_cache_size + activation_size;
         Ok(total_bytes / (1024 * 1024)) // Convert to MB
     }
 
+    /// Returns the context length actually in effect, derived from the
+    /// active `ContextManagement` strategy (falling back to `base.seq_len`
+    /// for `Dynamic`, which sizes itself from memory usage instead).
+    pub fn effective_context_length(&self) -> usize {
+        match &self.inference_params.context_management {
+            ContextManagement::Fixed { max_length } => *max_length,
+            ContextManagement::Sliding { window_size, .. } => *window_size,
+            ContextManagement::Dynamic { .. } => self.base.seq_len,
+        }
+    }
+
+    /// Breaks the model into the independently-quantizable parameter
+    /// regions [`plan_mixed_precision`](Self::plan_mixed_precision) budgets
+    /// over: one embedding table, one attention block and one feed-forward
+    /// block per transformer layer, and (unless `shared_classifier` ties it
+    /// to the embedding table) a separate output head. Element counts are
+    /// rough per-projection estimates (each projection taken as `dim x dim`
+    /// or `dim x hidden_dim`) rather than the layer's exact parameter count.
+    fn layer_regions(&self) -> Vec<(LayerKind, usize)> {
+        let dim = self.base.dim;
+        let hidden_dim = self.base.hidden_dim;
+        let vocab_size = self.base.vocab_size;
+
+        let mut regions = vec![(LayerKind::Embedding, vocab_size * dim)];
+
+        for layer in 0..self.base.n_layers {
+            // Q/K/V/O projections, each roughly dim x dim.
+            regions.push((LayerKind::Attention { layer }, 4 * dim * dim));
+            // SwiGLU gate/up/down projections, each roughly dim x hidden_dim.
+            regions.push((LayerKind::FeedForward { layer }, 3 * dim * hidden_dim));
+        }
+
+        if !self.base.shared_classifier {
+            regions.push((LayerKind::OutputHead, vocab_size * dim));
+        }
+
+        regions
+    }
+
+    /// Whether `plan_mixed_precision` should avoid downgrading this region
+    /// while a cheaper option remains, per `advanced.preserve_high_precision_layers`.
+    fn is_precision_protected(&self, kind: LayerKind) -> bool {
+        self.advanced.preserve_high_precision_layers
+            && matches!(
+                kind,
+                LayerKind::Embedding | LayerKind::OutputHead | LayerKind::Attention { .. }
+            )
+    }
+
+    /// Assigns a per-layer [`QuantizationLevel`] so the total estimated
+    /// parameter footprint fits under `memory_limits.max_memory_mb`,
+    /// trading off precision region-by-region instead of uniformly like
+    /// `quantization` does.
+    ///
+    /// Modeled as a fitting pass, akin to placing fixed-size regions into a
+    /// constrained address range: every region starts at `Fp16`, and while
+    /// the total exceeds budget, the region with the largest byte saving
+    /// from stepping one rung down the `Fp16 -> Int8 -> Int4` ladder is
+    /// downgraded next, repeating until the sum fits. Feed-forward blocks
+    /// are exhausted first; `Embedding`/`OutputHead`/`Attention` regions are
+    /// only downgraded once every feed-forward block is already at `Int4`
+    /// and the budget still isn't met (when `preserve_high_precision_layers`
+    /// is set — the default). Returns an error naming the minimum MB
+    /// required when even every region at `Int4` overflows the budget.
+    pub fn plan_mixed_precision(&self) -> Result<MixedPrecisionPlan> {
+        if self.base.n_layers == 0 {
+            anyhow::bail!("Invalid n_layers: must be positive, got 0");
+        }
+        if self.memory_limits.max_memory_mb == 0 {
+            anyhow::bail!("Invalid max_memory_mb: must be positive, got 0");
+        }
+
+        let budget_bytes = self.memory_limits.max_memory_mb * 1024 * 1024;
+
+        let mut plan: Vec<LayerQuantization> = self
+            .layer_regions()
+            .into_iter()
+            .map(|(kind, elements)| LayerQuantization {
+                kind,
+                elements,
+                level: QuantizationLevel::Fp16,
+            })
+            .collect();
+
+        let total_bytes = |plan: &[LayerQuantization]| -> usize {
+            plan.iter()
+                .map(|region| region.level.memory_usage(region.elements))
+                .sum()
+        };
+
+        // Two rounds: first only downgrade unprotected regions, then, if
+        // that alone doesn't fit, allow protected regions too.
+        for allow_protected in [false, true] {
+            while total_bytes(&plan) > budget_bytes {
+                let candidate = plan
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, region)| region.level.step_down().is_some())
+                    .filter(|(_, region)| {
+                        allow_protected || !self.is_precision_protected(region.kind)
+                    })
+                    .max_by_key(|(_, region)| {
+                        let current = region.level.memory_usage(region.elements);
+                        let next = region
+                            .level
+                            .step_down()
+                            .expect("filtered to regions with a step_down")
+                            .memory_usage(region.elements);
+                        current.saturating_sub(next)
+                    })
+                    .map(|(i, _)| i);
+
+                match candidate {
+                    Some(i) => plan[i].level = plan[i].level.step_down().unwrap(),
+                    None => break,
+                }
+            }
+
+            if total_bytes(&plan) <= budget_bytes {
+                return Ok(plan);
+            }
+        }
+
+        let min_required_mb = total_bytes(&plan).div_ceil(1024 * 1024);
+        anyhow::bail!(
+            "Model cannot fit under the {}MB memory budget even at Int4 on every layer; at least {}MB is required",
+            self.memory_limits.max_memory_mb,
+            min_required_mb
+        )
+    }
+
     /// Validates quantization compatibility with CPU target
     fn validate_quantization_compatibility(&self) -> bool {
-        match (self.quantization, self.cpu_target) {
-            (QuantizationLevel::Int4 { .. }, CpuTarget::RaspberryPi4) => true,
-            (QuantizationLevel::Int4 { .. }, CpuTarget::GenericArm) => true,
-            (QuantizationLevel::Int8 { .. }, _) => true,
-            (QuantizationLevel::Fp16, CpuTarget::RaspberryPi5) => true,
-            (QuantizationLevel::Fp16, CpuTarget::IntelN100) => true,
-            (QuantizationLevel::Fp32, CpuTarget::IntelN100) => true,
-            (QuantizationLevel::Fp32, CpuTarget::GenericX86) => true,
-            _ => false,
-        }
+        crate::quantization::is_quantization_compatible(self.quantization, self.cpu_target)
     }
 
     /// Loads configuration from TOML file
@@ -243,6 +473,8 @@ impl Default for ModelPaths {
             tokenizer_path: Some(PathBuf::from("tokenizer.json")),
             chat_template_path: Some(PathBuf::from("chat_template.json")),
             cache_dir: Some(PathBuf::from(".cache")),
+            session_path: None,
+            tuning_profile_path: None,
         }
     }
 }
@@ -273,6 +505,7 @@ impl Default for AdvancedConfig {
             log_level: LogLevel::Info,
             performance_monitoring: false,
             auto_save_config: true,
+            preserve_high_precision_layers: true,
         }
     }
 }
@@ -322,6 +555,7 @@ mod tests {
             vocab_size: 32000,
             group_size: 64,
             shared_classifier: true,
+            rope_theta: 10000.0,
         };
 
         let config = ExtendedModelConfig::new(base);
@@ -342,6 +576,7 @@ mod tests {
             vocab_size: 32000,
             group_size: 32,
             shared_classifier: true,
+            rope_theta: 10000.0,
         };
 
         let config = ExtendedModelConfig::new(base);
@@ -365,6 +600,7 @@ mod tests {
             vocab_size: 32000,
             group_size: 32,
             shared_classifier: true,
+            rope_theta: 10000.0,
         };
 
         let config = ExtendedModelConfig::for_cpu_target(base.clone(), CpuTarget::IntelN100);
@@ -373,4 +609,173 @@ mod tests {
         let config = ExtendedModelConfig::for_cpu_target(base.clone(), CpuTarget::RaspberryPi4);
         assert!(config.validate_quantization_compatibility());
     }
+
+    #[test]
+    fn test_plan_mixed_precision_fits_budget() {
+        let base = ModelConfig {
+            dim: 1024,
+            hidden_dim: 4096,
+            n_layers: 12,
+            n_heads: 16,
+            n_kv_heads: 4,
+            head_dim: 64,
+            seq_len: 2048,
+            vocab_size: 32000,
+            group_size: 32,
+            shared_classifier: true,
+            rope_theta: 10000.0,
+        };
+
+        let mut config = ExtendedModelConfig::new(base);
+        config.memory_limits.max_memory_mb = 256; // Tight enough to force downgrades.
+
+        let plan = config.plan_mixed_precision().unwrap();
+        let total_bytes: usize = plan
+            .iter()
+            .map(|region| region.level.memory_usage(region.elements))
+            .sum();
+        assert!(total_bytes <= config.memory_limits.max_memory_mb * 1024 * 1024);
+
+        // Attention/embedding should stay above Int4 as long as feed-forward
+        // blocks alone can make up the difference.
+        assert!(plan
+            .iter()
+            .any(|region| matches!(region.kind, LayerKind::FeedForward { .. })
+                && region.level == QuantizationLevel::Int4 { group_size: 64 }));
+    }
+
+    #[test]
+    fn test_plan_mixed_precision_reports_minimum_when_unfittable() {
+        let base = ModelConfig {
+            dim: 1024,
+            hidden_dim: 4096,
+            n_layers: 12,
+            n_heads: 16,
+            n_kv_heads: 4,
+            head_dim: 64,
+            seq_len: 2048,
+            vocab_size: 32000,
+            group_size: 32,
+            shared_classifier: true,
+            rope_theta: 10000.0,
+        };
+
+        let mut config = ExtendedModelConfig::new(base);
+        config.memory_limits.max_memory_mb = 1; // Impossible even at all-Int4.
+
+        let err = config.plan_mixed_precision().unwrap_err();
+        assert!(err.to_string().contains("at least"));
+    }
+
+    #[test]
+    fn test_plan_mixed_precision_downgrades_protected_regions_when_allowed() {
+        let base = ModelConfig {
+            dim: 1024,
+            hidden_dim: 4096,
+            n_layers: 12,
+            n_heads: 16,
+            n_kv_heads: 4,
+            head_dim: 64,
+            seq_len: 2048,
+            vocab_size: 32000,
+            group_size: 32,
+            shared_classifier: true,
+            rope_theta: 10000.0,
+        };
+
+        let mut config = ExtendedModelConfig::new(base);
+        config.advanced.preserve_high_precision_layers = false;
+        // Only an all-Int4 plan (about 125.6MB for this shape) fits under
+        // 126MB, so with protection off the Attention and Embedding regions
+        // get downgraded to Int4 along with the feed-forward blocks.
+        config.memory_limits.max_memory_mb = 126;
+
+        let plan = config.plan_mixed_precision().unwrap();
+        let total_bytes: usize = plan
+            .iter()
+            .map(|region| region.level.memory_usage(region.elements))
+            .sum();
+        assert!(total_bytes <= config.memory_limits.max_memory_mb * 1024 * 1024);
+
+        assert!(plan
+            .iter()
+            .any(|region| matches!(region.kind, LayerKind::Attention { .. })
+                && region.level == QuantizationLevel::Int4 { group_size: 64 }));
+        assert!(plan
+            .iter()
+            .any(|region| matches!(region.kind, LayerKind::Embedding)
+                && region.level == QuantizationLevel::Int4 { group_size: 64 }));
+    }
+
+    #[test]
+    fn test_plan_mixed_precision_rejects_zero_layers_and_zero_budget() {
+        let base = ModelConfig {
+            dim: 1024,
+            hidden_dim: 4096,
+            n_layers: 0,
+            n_heads: 16,
+            n_kv_heads: 4,
+            head_dim: 64,
+            seq_len: 2048,
+            vocab_size: 32000,
+            group_size: 32,
+            shared_classifier: true,
+            rope_theta: 10000.0,
+        };
+
+        let config = ExtendedModelConfig::new(base);
+        assert!(config.plan_mixed_precision().is_err());
+
+        let base = ModelConfig {
+            dim: 1024,
+            hidden_dim: 4096,
+            n_layers: 12,
+            n_heads: 16,
+            n_kv_heads: 4,
+            head_dim: 64,
+            seq_len: 2048,
+            vocab_size: 32000,
+            group_size: 32,
+            shared_classifier: true,
+            rope_theta: 10000.0,
+        };
+        let mut config = ExtendedModelConfig::new(base);
+        config.memory_limits.max_memory_mb = 0;
+        assert!(config.plan_mixed_precision().is_err());
+    }
+
+    #[test]
+    fn test_set_context_length_rejects_invalid_sink_size_without_mutating_window() {
+        let base = ModelConfig {
+            dim: 1024,
+            hidden_dim: 4096,
+            n_layers: 12,
+            n_heads: 16,
+            n_kv_heads: 4,
+            head_dim: 64,
+            seq_len: 4096,
+            vocab_size: 32000,
+            group_size: 32,
+            shared_classifier: true,
+            rope_theta: 10000.0,
+        };
+
+        let mut config = ExtendedModelConfig::new(base);
+        config.inference_params.context_management = ContextManagement::Sliding {
+            window_size: 2048,
+            sink_size: 2048,
+        };
+
+        // sink_size (2048) >= requested n_ctx (2048) must be rejected, and
+        // must leave window_size untouched rather than committing the bad
+        // value before returning the error.
+        assert!(config.set_context_length(2048).is_err());
+        match config.inference_params.context_management {
+            ContextManagement::Sliding { window_size, sink_size } => {
+                assert_eq!(window_size, 2048);
+                assert_eq!(sink_size, 2048);
+            }
+            _ => panic!("expected Sliding context management"),
+        }
+    }
 }
\ No newline at end of file
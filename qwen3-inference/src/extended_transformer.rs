@@ -1,16 +1,104 @@
 use anyhow::{Context, Result};
 
-use crate::cpu_optimizations::{CpuInfo, OptimizationStrategy};
+use crate::activation_arena::ActivationArena;
+use crate::cpu::TuningProfile;
+use crate::cpu_optimizations::{CpuInfo, OptimizationStrategy, ParallelStrategy};
 use crate::extended_config::ExtendedModelConfig;
+use crate::profiling::Profiler;
 use crate::transformer::{Transformer, TransformerBuilder};
 
 /// Extended transformer with advanced configuration support
-#[derive(Debug)]
 pub struct ExtendedTransformer {
     pub transformer: Transformer,
     pub config: ExtendedModelConfig,
     pub optimization_strategy: OptimizationStrategy,
     pub cpu_info: CpuInfo,
+    /// Binary event profiler, active when `config.advanced.performance_monitoring` is set.
+    pub profiler: Option<Profiler>,
+    /// Hand-tuned (or user-supplied, for `Custom` targets) execution
+    /// parameters resolved from `config.model_paths.tuning_profile_path`.
+    /// Its `threads` has already been folded into `optimization_strategy`'s
+    /// `parallel_strategy`; `cache_blocking`/`optimization_level` are kept
+    /// here for inspection rather than merged into `optimization_strategy`,
+    /// since the two cache-blocking representations aren't the same shape.
+    pub tuning_profile: TuningProfile,
+    /// Preallocated hidden-state/attention-score/GEMM-tile scratch space,
+    /// sized once from `config.base` and `optimization_strategy` at build
+    /// time. `transformer`'s forward pass does not use it.
+    pub activation_arena: ActivationArena,
+}
+
+impl std::fmt::Debug for ExtendedTransformer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ExtendedTransformer")
+            .field("transformer", &self.transformer)
+            .field("config", &self.config)
+            .field("optimization_strategy", &self.optimization_strategy)
+            .field("cpu_info", &self.cpu_info)
+            .field("profiler", &self.profiler.is_some())
+            .field("tuning_profile", &self.tuning_profile)
+            .field("activation_arena", &self.activation_arena)
+            .finish()
+    }
+}
+
+/// Builds a [`Profiler`] when `config.advanced.performance_monitoring` is enabled.
+/// Falls back to `.cache` when no `cache_dir` is configured, and logs a warning
+/// (without failing transformer construction) if the profiler can't be created.
+fn maybe_build_profiler(config: &ExtendedModelConfig) -> Option<Profiler> {
+    if !config.advanced.performance_monitoring {
+        return None;
+    }
+
+    let cache_dir = config
+        .model_paths
+        .cache_dir
+        .clone()
+        .unwrap_or_else(|| std::path::PathBuf::from(".cache"));
+
+    match Profiler::new(
+        &cache_dir,
+        config.cpu_target,
+        config.quantization,
+        config.advanced.log_level,
+    ) {
+        Ok(profiler) => Some(profiler),
+        Err(err) => {
+            log::warn!("Failed to start profiler: {err}");
+            None
+        }
+    }
+}
+
+/// Resolves the [`TuningProfile`] for the running host: loads
+/// `config.model_paths.tuning_profile_path` (if set) into a
+/// `TuningProfileRegistry`, then resolves the live `cpu::CpuTarget` against
+/// it. A missing/unparsable file falls back to an empty registry (so only
+/// the crate's hand-tuned targets and live `Generic` detection apply) with a
+/// warning rather than failing transformer construction.
+fn resolve_tuning_profile(config: &ExtendedModelConfig) -> TuningProfile {
+    let registry = match &config.model_paths.tuning_profile_path {
+        Some(path) => crate::cpu::TuningProfileRegistry::from_file(path).unwrap_or_else(|err| {
+            log::warn!("Failed to load tuning profile file {path:?}: {err}");
+            crate::cpu::TuningProfileRegistry::new()
+        }),
+        None => crate::cpu::TuningProfileRegistry::new(),
+    };
+
+    let target = crate::cpu::CpuTarget::detect();
+    let cpu_info = crate::cpu::CpuInfo::detect().unwrap_or_default();
+    registry.resolve(&target, &cpu_info)
+}
+
+/// Folds `tuning_profile.threads` into `strategy`'s `parallel_strategy`, the
+/// one dimension the two independent CPU-optimization subsystems agree on
+/// without needing a shared representation.
+fn apply_tuning_profile(strategy: &mut OptimizationStrategy, tuning_profile: &TuningProfile) {
+    if tuning_profile.threads > 1 {
+        strategy.parallel_strategy = ParallelStrategy::RayonThreads {
+            max_threads: tuning_profile.threads,
+        };
+    }
 }
 
 /// Builder for extended transformer
@@ -19,6 +107,10 @@ pub struct ExtendedTransformerBuilder {
     config: Option<ExtendedModelConfig>,
     optimization_strategy: Option<OptimizationStrategy>,
     cpu_info: Option<CpuInfo>,
+    /// Explicit per-option overrides, applied in [`build`](Self::build) at
+    /// the top of the `PICO_QWEN_<OPTION>` env-var / file / default
+    /// precedence chain. See [`crate::options`].
+    option_overrides: crate::options::OptionOverrides,
 }
 
 impl ExtendedTransformerBuilder {
@@ -57,33 +149,88 @@ impl ExtendedTransformerBuilder {
         self
     }
 
+    /// Explicitly overrides the quantization level, taking precedence over
+    /// `PICO_QWEN_QUANTIZATION` and whatever the config file/default set.
+    pub fn quantization(mut self, level: crate::quantization::QuantizationLevel) -> Self {
+        self.option_overrides.quantization = Some(level);
+        self
+    }
+
+    /// Explicitly overrides `memory_limits.max_memory_mb`, taking precedence
+    /// over `PICO_QWEN_MAX_MEMORY_MB` and the config file/default.
+    pub fn max_memory_mb(mut self, mb: usize) -> Self {
+        self.option_overrides.max_memory_mb = Some(mb);
+        self
+    }
+
+    /// Explicitly overrides `memory_limits.max_context_length`, taking
+    /// precedence over `PICO_QWEN_MAX_CONTEXT_LENGTH` and the config
+    /// file/default.
+    pub fn max_context_length(mut self, n: usize) -> Self {
+        self.option_overrides.max_context_length = Some(n);
+        self
+    }
+
+    /// Explicitly overrides `config.cpu_target`, taking precedence over
+    /// `PICO_QWEN_CPU_TARGET` and the config file/default.
+    pub fn cpu_target_override(mut self, target: crate::quantization::CpuTarget) -> Self {
+        self.option_overrides.cpu_target = Some(target);
+        self
+    }
+
+    /// Explicitly overrides `optimization_strategy.simd_width`, taking
+    /// precedence over `PICO_QWEN_SIMD_WIDTH` and the detected default.
+    pub fn simd_width(mut self, width: usize) -> Self {
+        self.option_overrides.simd_width = Some(width);
+        self
+    }
+
+    /// Explicitly overrides the GEMM tile size, taking precedence over
+    /// `PICO_QWEN_GEMM_TILE_SIZE` and the `simd_width`-derived default.
+    pub fn gemm_tile_size(mut self, tile: (usize, usize, usize)) -> Self {
+        self.option_overrides.gemm_tile_size = Some(tile);
+        self
+    }
+
     /// Builds the extended transformer
     pub fn build(self) -> Result<ExtendedTransformer> {
         let cpu_info = self.cpu_info.unwrap_or_else(CpuInfo::detect);
-        let optimization_strategy = self
+        let mut optimization_strategy = self
             .optimization_strategy
             .unwrap_or_else(|| OptimizationStrategy::for_cpu(&cpu_info));
 
-        let config = self
+        let mut config = self
             .config
             .ok_or_else(|| anyhow::anyhow!("Extended configuration is required"))?;
 
+        crate::options::apply(&mut config, &mut optimization_strategy, &self.option_overrides)
+            .context("Invalid runtime option override")?;
+
         // Validate configuration
         config
             .validate()
             .context("Invalid extended configuration")?;
 
+        let tuning_profile = resolve_tuning_profile(&config);
+        apply_tuning_profile(&mut optimization_strategy, &tuning_profile);
+
         // Build underlying transformer
         let transformer = TransformerBuilder::new(&config.model_paths.model_path.to_string_lossy())
             .with_ctx_length(Some(config.memory_limits.max_context_length))
             .build()
             .context("Failed to build transformer")?;
 
+        let profiler = maybe_build_profiler(&config);
+        let activation_arena = ActivationArena::new(&config, &optimization_strategy);
+
         Ok(ExtendedTransformer {
             transformer,
             config,
             optimization_strategy,
             cpu_info,
+            profiler,
+            tuning_profile,
+            activation_arena,
         })
     }
 
@@ -112,17 +259,26 @@ impl ExtendedTransformer {
         let config = ExtendedModelConfig::new(base_config.clone());
 
         let cpu_info = CpuInfo::detect();
-        let optimization_strategy = OptimizationStrategy::for_cpu(&cpu_info);
+        let mut optimization_strategy = OptimizationStrategy::for_cpu(&cpu_info);
 
         // Update config with actual model path
         let mut config = config;
         config.model_paths.model_path = model_path;
 
+        let tuning_profile = resolve_tuning_profile(&config);
+        apply_tuning_profile(&mut optimization_strategy, &tuning_profile);
+
+        let profiler = maybe_build_profiler(&config);
+        let activation_arena = ActivationArena::new(&config, &optimization_strategy);
+
         Ok(ExtendedTransformer {
             transformer,
             config,
             optimization_strategy,
             cpu_info,
+            profiler,
+            tuning_profile,
+            activation_arena,
         })
     }
 
@@ -141,16 +297,25 @@ impl ExtendedTransformer {
         let config = ExtendedModelConfig::for_cpu_target(base_config.clone(), cpu_target);
 
         let cpu_info = CpuInfo::detect();
-        let optimization_strategy = OptimizationStrategy::for_cpu(&cpu_info);
+        let mut optimization_strategy = OptimizationStrategy::for_cpu(&cpu_info);
 
         let mut config = config;
         config.model_paths.model_path = model_path;
 
+        let tuning_profile = resolve_tuning_profile(&config);
+        apply_tuning_profile(&mut optimization_strategy, &tuning_profile);
+
+        let profiler = maybe_build_profiler(&config);
+        let activation_arena = ActivationArena::new(&config, &optimization_strategy);
+
         Ok(ExtendedTransformer {
             transformer,
             config,
             optimization_strategy,
             cpu_info,
+            profiler,
+            tuning_profile,
+            activation_arena,
         })
     }
 
@@ -179,6 +344,16 @@ impl ExtendedTransformer {
         &self.cpu_info
     }
 
+    /// Gets the active profiler, if `config.advanced.performance_monitoring` is enabled.
+    pub fn profiler(&self) -> Option<&Profiler> {
+        self.profiler.as_ref()
+    }
+
+    /// Gets mutable access to the preallocated activation scratch space.
+    pub fn activation_arena(&mut self) -> &mut ActivationArena {
+        &mut self.activation_arena
+    }
+
     /// Updates configuration and revalidates
     pub fn update_config(&mut self, updater: impl FnOnce(&mut ExtendedModelConfig)) -> Result<()> {
         updater(&mut self.config);
@@ -197,7 +372,8 @@ impl ExtendedTransformer {
 
     /// Gets memory usage statistics
     pub fn memory_stats(&self) -> MemoryStats {
-        let estimated_usage = self.config.estimate_memory_usage().unwrap_or(0);
+        let arena_mb = self.activation_arena.footprint_bytes() / (1024 * 1024);
+        let estimated_usage = self.config.estimate_memory_usage().unwrap_or(0) + arena_mb;
 
         MemoryStats {
             estimated_memory_mb: estimated_usage,
@@ -208,6 +384,31 @@ impl ExtendedTransformer {
         }
     }
 
+    /// Computes a per-layer mixed-precision plan (see
+    /// [`ExtendedModelConfig::plan_mixed_precision`]), stores it on
+    /// `self.config` so a subsequent `save_config` persists it, and returns
+    /// `MemoryStats` reflecting the heterogeneous per-layer layout rather
+    /// than `memory_stats`'s uniform-quantization estimate.
+    pub fn plan_mixed_precision(&mut self) -> Result<MemoryStats> {
+        let plan = self.config.plan_mixed_precision()?;
+
+        let estimated_bytes: usize = plan
+            .iter()
+            .map(|region| region.level.memory_usage(region.elements))
+            .sum();
+        let estimated_memory_mb = estimated_bytes / (1024 * 1024);
+
+        self.config.layer_quantization_plan = Some(plan);
+
+        Ok(MemoryStats {
+            estimated_memory_mb,
+            max_allowed_mb: self.config.memory_limits.max_memory_mb,
+            utilization_ratio: estimated_memory_mb as f32
+                / self.config.memory_limits.max_memory_mb as f32,
+            quantization_savings: self.calculate_quantization_savings(),
+        })
+    }
+
     /// Calculates memory savings from quantization
     fn calculate_quantization_savings(&self) -> QuantizationSavings {
         let original_size = self.config.base.dim * self.config.base.vocab_size * 4; // 4 bytes per float
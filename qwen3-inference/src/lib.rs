@@ -2,6 +2,7 @@
 //!
 //! This crate will provide inference functionality for Qwen3 models in the future.
 
+mod activation_arena;
 pub mod cloud;
 pub mod configuration;
 pub mod cpu;
@@ -9,12 +10,17 @@ mod cpu_optimizations;
 mod extended_config;
 pub mod extended_transformer;
 pub mod generation;
+pub mod memory_limiter;
+pub mod options;
+pub mod profiling;
 pub mod quantization;
 pub mod sampler;
+pub mod session;
 pub mod tensor;
 pub mod tokenizer;
 pub mod transformer;
-mod utils;
+pub mod utils;
+mod weight_table;
 
 use anyhow::Result;
 use log::debug;
@@ -25,9 +31,11 @@ use crate::sampler::Sampler;
 use crate::tokenizer::Tokenizer;
 use crate::transformer::TransformerBuilder;
 
-pub use crate::configuration::ModelConfig;
+pub use crate::activation_arena::ActivationArena;
+pub use crate::configuration::{read_model_metadata, ContainerFormat, ModelConfig, ModelMetadata};
 pub use crate::cpu_optimizations::{
     CacheBlockingStrategy, CpuFeature, CpuInfo, CpuVendor, OptimizationStrategy, ParallelStrategy,
+    ThermalSample,
 };
 pub use crate::extended_config::{
     AdvancedConfig, ContextManagement, ExtendedModelConfig, InferenceParameters, LogLevel,
@@ -36,7 +44,13 @@ pub use crate::extended_config::{
 pub use crate::extended_transformer::{
     ExtendedTransformer, ExtendedTransformerBuilder, MemoryStats, QuantizationSavings,
 };
+pub use crate::memory_limiter::{estimate_kv_cache_bytes, MemoryLimiter, Reservation};
+pub use crate::options::OptionOverrides;
+pub use crate::profiling::{Profiler, SpanGuard};
 pub use crate::quantization::{CloudConfig, CpuTarget, MemoryLimits, QuantizationLevel};
+pub use crate::weight_table::{
+    ArchivedTensorDescriptor, ArchivedWeightTable, TensorDType, TensorDescriptor, WeightTable,
+};
 
 #[derive(Debug, Clone)]
 pub struct InferenceConfig {
@@ -50,6 +64,24 @@ pub struct InferenceConfig {
     pub enable_thinking: bool,
     pub seed: u64,
     pub max_tokens: usize,
+    /// Opt-in thermal throttling threshold in Celsius. When set, `run_inference`
+    /// checks `ThermalSample::sample()` against it before generating and
+    /// backs off with a short sleep once crossed, so long chats on
+    /// passively-cooled boards (Pi 4/5, N100) degrade gracefully instead of
+    /// hitting the SoC's hard thermal cliff.
+    pub thermal_guard: Option<f32>,
+    /// Opt-in memory-pressure downgrade ratio (the `downgrade_ratio` passed
+    /// to `DynamicQuantController::new`). When set, `run_inference` samples
+    /// this process's resident memory once before generating and logs a
+    /// recommended quantization transition — the same one-shot treatment
+    /// `thermal_guard` gives CPU temperature. This crate's own
+    /// `generation::generate`/`generation::chat` have no per-token loop to
+    /// hook hysteresis into yet; `cloud::local::LocalProvider::generate`
+    /// does have one and ticks a persistent controller there once per
+    /// token, shrinking its effective context window on a sustained
+    /// downgrade recommendation (see `dynamic_quant_guard` on
+    /// `CloudProviderConfig`).
+    pub dynamic_quant_guard: Option<f32>,
 }
 
 impl InferenceConfig {
@@ -70,6 +102,8 @@ pub struct InferenceConfigBuilder {
     enable_thinking: Option<bool>,
     seed: Option<u64>,
     max_tokens: Option<usize>,
+    thermal_guard: Option<f32>,
+    dynamic_quant_guard: Option<f32>,
 }
 
 impl InferenceConfigBuilder {
@@ -113,6 +147,14 @@ impl InferenceConfigBuilder {
         self.max_tokens = max_tokens;
         self
     }
+    pub fn thermal_guard(mut self, threshold_c: Option<f32>) -> Self {
+        self.thermal_guard = threshold_c;
+        self
+    }
+    pub fn dynamic_quant_guard(mut self, downgrade_ratio: Option<f32>) -> Self {
+        self.dynamic_quant_guard = downgrade_ratio;
+        self
+    }
     pub fn build(self) -> Result<InferenceConfig, String> {
         Ok(InferenceConfig {
             checkpoint_path: self.checkpoint_path.ok_or("checkpoint_path is required")?,
@@ -130,10 +172,55 @@ impl InferenceConfigBuilder {
                     .as_secs()
             }),
             max_tokens: self.max_tokens.unwrap_or(50),
+            thermal_guard: self.thermal_guard,
+            dynamic_quant_guard: self.dynamic_quant_guard,
         })
     }
 }
 
+/// Blocks in short increments until `ThermalSample::sample()` drops back
+/// below `threshold_c`, logging once per escalation rather than once per
+/// poll. This is the coarse-grained guard available today; per-token
+/// pacing requires a hook inside the (not yet implemented) token-generation
+/// loop in `generation::generate`/`generation::chat`.
+fn wait_for_thermal_headroom(threshold_c: f32) {
+    let mut logged = false;
+    loop {
+        let sample = crate::cpu_optimizations::ThermalSample::sample();
+        if !sample.exceeds(threshold_c) {
+            return;
+        }
+        if !logged {
+            log::warn!(
+                "Thermal guard: CPU at {:?}C >= {threshold_c}C threshold, pacing generation",
+                sample.temperature_c
+            );
+            logged = true;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(250));
+    }
+}
+
+/// Samples this process's resident memory once via
+/// `quantization::current_rss_mb` and feeds it to `controller.tick`, logging
+/// any recommended quantization transition. This is a one-shot pre-generation
+/// check, the same coarse treatment `wait_for_thermal_headroom` gives CPU
+/// temperature: it catches pressure already present before generation
+/// starts, not pressure that builds up mid-response, since that needs a hook
+/// inside a per-token loop — `cloud::local::LocalProvider::generate` has one
+/// and ticks its own controller there every token instead of once here.
+fn apply_dynamic_quant_guard(controller: &mut crate::quantization::DynamicQuantController) {
+    let Some(usage_mb) = crate::quantization::current_rss_mb() else {
+        return;
+    };
+    if let Some(new_level) = controller.tick(usage_mb) {
+        log::info!(
+            "Dynamic quantization guard recommends {new_level} for the next run \
+             (already-loaded weights for this run are not re-quantized live)"
+        );
+    }
+}
+
 /// Runs inference.
 pub fn run_inference(inference_config: InferenceConfig) -> Result<()> {
     debug!("{inference_config:#?}");
@@ -164,6 +251,27 @@ pub fn run_inference(inference_config: InferenceConfig) -> Result<()> {
     let prompt = inference_config.prompt.as_deref();
     let system_prompt = inference_config.system_prompt.as_deref();
 
+    if let Some(threshold_c) = inference_config.thermal_guard {
+        wait_for_thermal_headroom(threshold_c);
+    }
+
+    if let Some(downgrade_ratio) = inference_config.dynamic_quant_guard {
+        let cpu_target = crate::quantization::CpuTarget::detect();
+        let max_memory_mb = crate::quantization::MemoryLimits::for_cpu_target(cpu_target).max_memory_mb;
+        let mut controller = crate::quantization::DynamicQuantController::new(
+            cpu_target.optimal_quantization(),
+            cpu_target,
+            max_memory_mb,
+            downgrade_ratio,
+        )
+        // This guard only ever ticks once, before generation starts, so
+        // hysteresis over several samples (the default, meant for the
+        // per-token loop in `LocalProvider::generate`) would never trip —
+        // require just this one sample to recommend a downgrade.
+        .with_hysteresis_samples(1);
+        apply_dynamic_quant_guard(&mut controller);
+    }
+
     // Run
     match inference_config.mode.as_str() {
         "generate" => generate(
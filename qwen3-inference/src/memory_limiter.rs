@@ -0,0 +1,154 @@
+//! Runtime memory admission control.
+//!
+//! [`crate::quantization::MemoryLimits`] and
+//! [`crate::extended_config::ExtendedModelConfig::estimate_memory_usage`]
+//! only validate a config's *static* footprint; nothing stops several
+//! concurrently-handled requests from collectively overrunning
+//! `max_memory_mb` once the server is actually serving traffic.
+//! [`MemoryLimiter`] tracks a running total of reserved bytes and hands out
+//! RAII [`Reservation`]s that release automatically on drop, so admission
+//! control composes with early request termination (errors, disconnects)
+//! for free.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use crate::quantization::QuantizationLevel;
+
+/// Admission-controls bytes against a fixed budget. Cheap to clone (an
+/// `Arc` around a single atomic counter), so one instance can be shared
+/// across all requests served by a model.
+#[derive(Debug, Clone)]
+pub struct MemoryLimiter {
+    reserved_bytes: Arc<AtomicU64>,
+    capacity_bytes: u64,
+}
+
+impl MemoryLimiter {
+    /// Builds a limiter enforcing `max_memory_mb`, e.g. from
+    /// `ExtendedModelConfig.memory_limits.max_memory_mb`.
+    pub fn new(max_memory_mb: usize) -> Self {
+        Self {
+            reserved_bytes: Arc::new(AtomicU64::new(0)),
+            capacity_bytes: max_memory_mb as u64 * 1024 * 1024,
+        }
+    }
+
+    /// Bytes currently reserved across all live [`Reservation`]s.
+    pub fn reserved_bytes(&self) -> u64 {
+        self.reserved_bytes.load(Ordering::Relaxed)
+    }
+
+    pub fn capacity_bytes(&self) -> u64 {
+        self.capacity_bytes
+    }
+
+    /// Attempts to reserve `bytes`. Returns `None` rather than an error
+    /// when the reservation would exceed the budget, so callers decide how
+    /// to report the rejection (e.g. a 503 naming the requested and
+    /// available amounts) without this module knowing about HTTP.
+    pub fn try_reserve(&self, bytes: u64) -> Option<Reservation> {
+        let mut current = self.reserved_bytes.load(Ordering::Relaxed);
+        loop {
+            let next = current.checked_add(bytes)?;
+            if next > self.capacity_bytes {
+                return None;
+            }
+            match self.reserved_bytes.compare_exchange_weak(
+                current,
+                next,
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => {
+                    return Some(Reservation {
+                        reserved_bytes: self.reserved_bytes.clone(),
+                        bytes,
+                    })
+                }
+                Err(observed) => current = observed,
+            }
+        }
+    }
+}
+
+/// RAII guard for a reservation made via [`MemoryLimiter::try_reserve`].
+/// Releases its bytes back to the limiter's budget when dropped, whether
+/// that's on normal completion or an early return/panic unwind.
+#[derive(Debug)]
+pub struct Reservation {
+    reserved_bytes: Arc<AtomicU64>,
+    bytes: u64,
+}
+
+impl Reservation {
+    pub fn bytes(&self) -> u64 {
+        self.bytes
+    }
+}
+
+impl Drop for Reservation {
+    fn drop(&mut self) {
+        self.reserved_bytes.fetch_sub(self.bytes, Ordering::Relaxed);
+    }
+}
+
+/// Estimates the KV-cache footprint (bytes) for a single request's
+/// generation, given the model shape, the request's sequence length, and
+/// the active quantization level. Mirrors the `kv_cache_size` term in
+/// [`crate::extended_config::ExtendedModelConfig::estimate_memory_usage`],
+/// but scoped to one request's `seq_len` rather than the model's whole
+/// configured context, so concurrent requests are charged independently.
+pub fn estimate_kv_cache_bytes(
+    n_layers: usize,
+    n_kv_heads: usize,
+    head_dim: usize,
+    seq_len: usize,
+    quantization: QuantizationLevel,
+) -> u64 {
+    // K and V caches, one (n_kv_heads * head_dim) vector per token per layer.
+    let elements = n_layers * n_kv_heads * head_dim * seq_len * 2;
+    quantization.memory_usage(elements) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reserve_within_budget_succeeds_and_releases_on_drop() {
+        let limiter = MemoryLimiter::new(1);
+        let one_mb = 1024 * 1024;
+
+        {
+            let reservation = limiter.try_reserve(one_mb).expect("fits exactly");
+            assert_eq!(limiter.reserved_bytes(), one_mb);
+            assert_eq!(reservation.bytes(), one_mb);
+        }
+
+        assert_eq!(limiter.reserved_bytes(), 0);
+    }
+
+    #[test]
+    fn test_reserve_beyond_budget_is_rejected() {
+        let limiter = MemoryLimiter::new(1);
+        let over_budget = 2 * 1024 * 1024;
+
+        assert!(limiter.try_reserve(over_budget).is_none());
+        assert_eq!(limiter.reserved_bytes(), 0);
+    }
+
+    #[test]
+    fn test_concurrent_reservations_share_the_same_budget() {
+        let limiter = MemoryLimiter::new(2);
+        let one_mb = 1024 * 1024;
+
+        let first = limiter.try_reserve(one_mb).expect("first MB fits");
+        let second = limiter.try_reserve(one_mb).expect("second MB fits");
+        assert!(limiter.try_reserve(1).is_none());
+
+        drop(first);
+        assert!(limiter.try_reserve(one_mb).is_some());
+        drop(second);
+    }
+}
@@ -0,0 +1,185 @@
+//! Unified runtime-configuration registry layered on top of
+//! [`ExtendedModelConfig`]/[`OptimizationStrategy`].
+//!
+//! Six tunables (quantization level, `max_memory_mb`, `max_context_length`,
+//! `cpu_target`, `simd_width`, GEMM tile size) can each come from four
+//! places, in increasing precedence: a compiled-in default, the loaded TOML
+//! file, a `PICO_QWEN_<OPTION>` environment variable, and an explicit
+//! [`ExtendedTransformerBuilder`](crate::extended_transformer::ExtendedTransformerBuilder)
+//! call. [`apply`] resolves all six and writes the result back into the
+//! config/strategy the builder is about to validate and build from.
+
+use anyhow::{Context, Result};
+
+use crate::cpu_optimizations::OptimizationStrategy;
+use crate::extended_config::ExtendedModelConfig;
+use crate::quantization::{CpuTarget, QuantizationLevel};
+
+/// Explicit, builder-supplied overrides — the highest-precedence layer
+/// `apply` considers. `None` for a field means "no explicit override; fall
+/// back to env, then the file/default value already on `config`/`strategy`".
+#[derive(Debug, Clone, Default)]
+pub struct OptionOverrides {
+    pub quantization: Option<QuantizationLevel>,
+    pub max_memory_mb: Option<usize>,
+    pub max_context_length: Option<usize>,
+    pub cpu_target: Option<CpuTarget>,
+    pub simd_width: Option<usize>,
+    pub gemm_tile_size: Option<(usize, usize, usize)>,
+}
+
+/// Resolves one tunable: `explicit` if set, else the `env_var` parsed via
+/// `parse` if set, else `current` (the value already on the config/strategy
+/// from the TOML file or its `Default`/`for_cpu_target` constructor).
+fn resolve_override<T>(
+    current: T,
+    env_var: &str,
+    parse: impl Fn(&str) -> Result<T>,
+    explicit: Option<T>,
+) -> Result<T> {
+    if let Some(value) = explicit {
+        return Ok(value);
+    }
+
+    if let Ok(raw) = std::env::var(env_var) {
+        return parse(&raw).with_context(|| format!("invalid value for {env_var} ({raw:?})"));
+    }
+
+    Ok(current)
+}
+
+/// Parses `PICO_QWEN_GEMM_TILE_SIZE`'s `"m,n,k"` format. No `FromStr` exists
+/// for a bare tuple, so this is handled separately from the other options,
+/// which all reuse an existing `FromStr` impl.
+fn parse_gemm_tile_size(raw: &str) -> Result<(usize, usize, usize)> {
+    let dims: Vec<&str> = raw.split(',').collect();
+    let [m, n, k] = dims.as_slice() else {
+        anyhow::bail!("expected 3 comma-separated dimensions (\"m,n,k\"), got {}", dims.len());
+    };
+
+    Ok((
+        m.trim().parse().context("invalid m dimension")?,
+        n.trim().parse().context("invalid n dimension")?,
+        k.trim().parse().context("invalid k dimension")?,
+    ))
+}
+
+/// Resolves every tunable in precedence order (explicit > env > file >
+/// default) and writes the result back into `config`/`strategy`, so the
+/// builder's subsequent `config.validate()` sees the fully-overridden
+/// configuration. Fails with a message naming the offending
+/// `PICO_QWEN_<OPTION>` variable or builder call.
+pub fn apply(
+    config: &mut ExtendedModelConfig,
+    strategy: &mut OptimizationStrategy,
+    overrides: &OptionOverrides,
+) -> Result<()> {
+    config.quantization = resolve_override(
+        config.quantization,
+        "PICO_QWEN_QUANTIZATION",
+        |raw| raw.parse::<QuantizationLevel>().map_err(|err| anyhow::anyhow!(err)),
+        overrides.quantization,
+    )?;
+
+    config.memory_limits.max_memory_mb = resolve_override(
+        config.memory_limits.max_memory_mb,
+        "PICO_QWEN_MAX_MEMORY_MB",
+        |raw| raw.parse::<usize>().context("must be a positive integer"),
+        overrides.max_memory_mb,
+    )?;
+    if config.memory_limits.max_memory_mb == 0 {
+        anyhow::bail!("invalid value for PICO_QWEN_MAX_MEMORY_MB: must be greater than zero");
+    }
+
+    config.memory_limits.max_context_length = resolve_override(
+        config.memory_limits.max_context_length,
+        "PICO_QWEN_MAX_CONTEXT_LENGTH",
+        |raw| raw.parse::<usize>().context("must be a positive integer"),
+        overrides.max_context_length,
+    )?;
+    if config.memory_limits.max_context_length == 0 {
+        anyhow::bail!("invalid value for PICO_QWEN_MAX_CONTEXT_LENGTH: must be greater than zero");
+    }
+
+    config.cpu_target = resolve_override(
+        config.cpu_target,
+        "PICO_QWEN_CPU_TARGET",
+        |raw| raw.parse::<CpuTarget>().map_err(|err| anyhow::anyhow!(err)),
+        overrides.cpu_target,
+    )?;
+
+    strategy.simd_width = resolve_override(
+        strategy.simd_width,
+        "PICO_QWEN_SIMD_WIDTH",
+        |raw| raw.parse::<usize>().context("must be a positive integer"),
+        overrides.simd_width,
+    )?;
+    if ![2, 4, 8, 16].contains(&strategy.simd_width) {
+        anyhow::bail!("invalid value for PICO_QWEN_SIMD_WIDTH: must be one of 2, 4, 8, 16");
+    }
+
+    let tile = resolve_override(
+        strategy.gemm_tile_size(),
+        "PICO_QWEN_GEMM_TILE_SIZE",
+        parse_gemm_tile_size,
+        overrides.gemm_tile_size,
+    )?;
+    if tile.0 == 0 || tile.1 == 0 || tile.2 == 0 {
+        anyhow::bail!("invalid value for PICO_QWEN_GEMM_TILE_SIZE: dimensions must be greater than zero");
+    }
+    strategy.gemm_tile_override = Some(tile);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_explicit_override_wins_over_env() {
+        std::env::set_var("PICO_QWEN_MAX_MEMORY_MB", "1234");
+        let resolved = resolve_override(
+            512usize,
+            "PICO_QWEN_MAX_MEMORY_MB",
+            |raw| raw.parse::<usize>().context("must be a positive integer"),
+            Some(4096),
+        )
+        .unwrap();
+        std::env::remove_var("PICO_QWEN_MAX_MEMORY_MB");
+        assert_eq!(resolved, 4096);
+    }
+
+    #[test]
+    fn test_env_wins_over_file_default() {
+        std::env::set_var("PICO_QWEN_MAX_CONTEXT_LENGTH", "2048");
+        let resolved = resolve_override(
+            4096usize,
+            "PICO_QWEN_MAX_CONTEXT_LENGTH",
+            |raw| raw.parse::<usize>().context("must be a positive integer"),
+            None,
+        )
+        .unwrap();
+        std::env::remove_var("PICO_QWEN_MAX_CONTEXT_LENGTH");
+        assert_eq!(resolved, 2048);
+    }
+
+    #[test]
+    fn test_falls_back_to_current_value() {
+        let resolved = resolve_override(
+            4096usize,
+            "PICO_QWEN_NONEXISTENT_OPTION_FOR_TEST",
+            |raw| raw.parse::<usize>().context("must be a positive integer"),
+            None,
+        )
+        .unwrap();
+        assert_eq!(resolved, 4096);
+    }
+
+    #[test]
+    fn test_parse_gemm_tile_size() {
+        assert_eq!(parse_gemm_tile_size("8,8,4").unwrap(), (8, 8, 4));
+        assert!(parse_gemm_tile_size("8,8").is_err());
+        assert!(parse_gemm_tile_size("a,8,4").is_err());
+    }
+}
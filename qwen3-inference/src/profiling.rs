@@ -0,0 +1,305 @@
+//! Lightweight binary profiling subsystem, modeled after `measureme`.
+//!
+//! When `AdvancedConfig.performance_monitoring` is enabled, a [`Profiler`] is
+//! attached to the running [`ExtendedTransformer`](crate::extended_transformer::ExtendedTransformer)
+//! and records spans (`start_span`) and instant counters (`record_instant`) to
+//! a compact `.mm_events` file under `ModelPaths.cache_dir`. The format is
+//! intentionally simple: a fixed header followed by fixed-size event records,
+//! so an offline tool can decode it without depending on this crate.
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Mutex;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+
+use crate::extended_config::LogLevel;
+use crate::quantization::{CpuTarget, QuantizationLevel};
+
+/// Magic bytes identifying a `.mm_events` file.
+const EVENTS_MAGIC: [u8; 4] = *b"QMM1";
+/// Number of events buffered before a flush to disk.
+const FLUSH_CHUNK_EVENTS: usize = 256;
+
+/// Kind tag stored in each event record.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventKind {
+    Span = 0,
+    Instant = 1,
+}
+
+/// A single fixed-size raw event record, written in little-endian.
+///
+/// Layout: `{string_id: u32, thread_id: u32, start_ns: u64, end_ns: u64, kind: u8}`
+#[derive(Debug, Clone, Copy)]
+struct EventRecord {
+    string_id: u32,
+    thread_id: u32,
+    start_ns: u64,
+    end_ns: u64,
+    kind: EventKind,
+}
+
+impl EventRecord {
+    const ENCODED_SIZE: usize = 4 + 4 + 8 + 8 + 1;
+
+    fn write_to(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(&self.string_id.to_le_bytes());
+        buf.extend_from_slice(&self.thread_id.to_le_bytes());
+        buf.extend_from_slice(&self.start_ns.to_le_bytes());
+        buf.extend_from_slice(&self.end_ns.to_le_bytes());
+        buf.push(self.kind as u8);
+    }
+}
+
+/// Deduplicated interner for event labels, so repeated span names only
+/// pay the string-table cost once.
+#[derive(Debug, Default)]
+struct StringTable {
+    strings: Vec<String>,
+    next_id: AtomicU32,
+}
+
+impl StringTable {
+    fn intern(&mut self, label: &str) -> u32 {
+        if let Some(pos) = self.strings.iter().position(|s| s == label) {
+            return pos as u32;
+        }
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.strings.push(label.to_string());
+        id
+    }
+}
+
+struct ProfilerInner {
+    strings: StringTable,
+    events: Vec<EventRecord>,
+    writer: BufWriter<File>,
+    start: Instant,
+}
+
+/// Binary profiler that appends span and counter events to a `.mm_events`
+/// file. Cheap to clone; internal state is behind a mutex so spans can be
+/// recorded from any thread.
+#[derive(Clone)]
+pub struct Profiler {
+    inner: std::sync::Arc<Mutex<ProfilerInner>>,
+    log_level: LogLevel,
+}
+
+impl Profiler {
+    /// Creates a profiler writing to `cache_dir/<run>.mm_events`, recording
+    /// the detected `cpu_target` and `quantization` in the file header.
+    pub fn new(
+        cache_dir: &Path,
+        cpu_target: CpuTarget,
+        quantization: QuantizationLevel,
+        log_level: LogLevel,
+    ) -> Result<Self> {
+        std::fs::create_dir_all(cache_dir)
+            .with_context(|| format!("Failed to create cache dir: {cache_dir:?}"))?;
+
+        let run_id = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        let path: PathBuf = cache_dir.join(format!("run-{run_id}.mm_events"));
+
+        let file = File::create(&path)
+            .with_context(|| format!("Failed to create profiling file: {path:?}"))?;
+        let mut writer = BufWriter::new(file);
+
+        write_header(&mut writer, cpu_target, quantization)
+            .context("Failed to write profiling header")?;
+
+        Ok(Self {
+            inner: std::sync::Arc::new(Mutex::new(ProfilerInner {
+                strings: StringTable::default(),
+                events: Vec::with_capacity(FLUSH_CHUNK_EVENTS),
+                writer,
+                start: Instant::now(),
+            })),
+            log_level,
+        })
+    }
+
+    /// Begins a named span. The returned guard records the end timestamp
+    /// when dropped.
+    pub fn start_span(&self, label: &str) -> SpanGuard {
+        let string_id = {
+            let mut inner = self.inner.lock().unwrap();
+            inner.strings.intern(label)
+        };
+
+        SpanGuard {
+            profiler: self.clone(),
+            string_id,
+            start: Instant::now(),
+        }
+    }
+
+    /// Records an instantaneous counter value (e.g. tokens/sec, bytes/sec).
+    /// `value` is packed into `end_ns` so the record stays fixed-size.
+    pub fn record_instant(&self, label: &str, value: u64) {
+        let mut inner = self.inner.lock().unwrap();
+        let string_id = inner.strings.intern(label);
+        let now_ns = inner.start.elapsed().as_nanos() as u64;
+
+        inner.events.push(EventRecord {
+            string_id,
+            thread_id: current_thread_id(),
+            start_ns: now_ns,
+            end_ns: value,
+            kind: EventKind::Instant,
+        });
+
+        if inner.events.len() >= FLUSH_CHUNK_EVENTS {
+            let _ = flush_locked(&mut inner);
+        }
+    }
+
+    fn record_span(&self, string_id: u32, start_ns: u64, end_ns: u64) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.events.push(EventRecord {
+            string_id,
+            thread_id: current_thread_id(),
+            start_ns,
+            end_ns,
+            kind: EventKind::Span,
+        });
+
+        if inner.events.len() >= FLUSH_CHUNK_EVENTS {
+            let _ = flush_locked(&mut inner);
+        }
+    }
+
+    /// Flushes buffered events to disk. Safe to call repeatedly.
+    pub fn flush(&self) -> Result<()> {
+        let mut inner = self.inner.lock().unwrap();
+        flush_locked(&mut inner)
+    }
+
+    /// Whether spans should also be logged at [`LogLevel::Trace`].
+    pub fn verbose(&self) -> bool {
+        self.log_level == LogLevel::Trace || self.log_level == LogLevel::Debug
+    }
+}
+
+impl Drop for ProfilerInner {
+    fn drop(&mut self) {
+        let _ = self.writer.flush();
+    }
+}
+
+fn flush_locked(inner: &mut ProfilerInner) -> Result<()> {
+    if inner.events.is_empty() {
+        return Ok(());
+    }
+
+    let mut buf = Vec::with_capacity(inner.events.len() * EventRecord::ENCODED_SIZE);
+    for event in inner.events.drain(..) {
+        event.write_to(&mut buf);
+    }
+
+    inner.writer.write_all(&buf).context("Failed to write profiling events")?;
+    inner.writer.flush().context("Failed to flush profiling writer")?;
+    Ok(())
+}
+
+fn write_header(
+    writer: &mut BufWriter<File>,
+    cpu_target: CpuTarget,
+    quantization: QuantizationLevel,
+) -> Result<()> {
+    let cpu_target_str = cpu_target.to_string();
+    let quantization_str = quantization.to_string();
+
+    writer.write_all(&EVENTS_MAGIC)?;
+    writer.write_all(&clock_resolution_ns().to_le_bytes())?;
+
+    writer.write_all(&(cpu_target_str.len() as u32).to_le_bytes())?;
+    writer.write_all(cpu_target_str.as_bytes())?;
+
+    writer.write_all(&(quantization_str.len() as u32).to_le_bytes())?;
+    writer.write_all(quantization_str.as_bytes())?;
+
+    Ok(())
+}
+
+/// Best-effort estimate of the monotonic clock resolution, in nanoseconds.
+fn clock_resolution_ns() -> u64 {
+    let start = Instant::now();
+    let mut prev = start;
+    loop {
+        let now = Instant::now();
+        if now != prev {
+            return now.duration_since(prev).as_nanos() as u64;
+        }
+        prev = now;
+        if start.elapsed().as_millis() > 5 {
+            // Couldn't observe a tick quickly; fall back to a typical value.
+            return 100;
+        }
+    }
+}
+
+fn current_thread_id() -> u32 {
+    // std::thread::ThreadId doesn't expose a stable numeric value, so hash it
+    // down to a u32 which is all the fixed-size record format needs.
+    use std::hash::{Hash, Hasher};
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    std::thread::current().id().hash(&mut hasher);
+    hasher.finish() as u32
+}
+
+/// RAII guard returned by [`Profiler::start_span`]. Recording the end
+/// timestamp happens automatically on drop.
+pub struct SpanGuard {
+    profiler: Profiler,
+    string_id: u32,
+    start: Instant,
+}
+
+impl Drop for SpanGuard {
+    fn drop(&mut self) {
+        let base = {
+            let inner = self.profiler.inner.lock().unwrap();
+            inner.start
+        };
+        let start_ns = self.start.duration_since(base).as_nanos() as u64;
+        let end_ns = Instant::now().duration_since(base).as_nanos() as u64;
+        self.profiler.record_span(self.string_id, start_ns, end_ns);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_span_and_instant_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let profiler = Profiler::new(
+            dir.path(),
+            CpuTarget::GenericX86,
+            QuantizationLevel::Int8 { group_size: 64 },
+            LogLevel::Info,
+        )
+        .unwrap();
+
+        {
+            let _span = profiler.start_span("prefill");
+        }
+        profiler.record_instant("tokens_per_sec", 42);
+        profiler.flush().unwrap();
+
+        let mut entries = std::fs::read_dir(dir.path()).unwrap();
+        let entry = entries.next().unwrap().unwrap();
+        assert!(entry.path().extension().unwrap() == "mm_events");
+        assert!(entry.metadata().unwrap().len() > EVENTS_MAGIC.len() as u64);
+    }
+}
@@ -106,6 +106,22 @@ impl std::str::FromStr for QuantizationLevel {
     }
 }
 
+/// Checks whether a quantization level is supported on a given CPU target.
+/// Shared by `ExtendedModelConfig::validate_quantization_compatibility` and
+/// [`DynamicQuantController`], which must agree on what's a legal downgrade.
+pub fn is_quantization_compatible(level: QuantizationLevel, cpu_target: CpuTarget) -> bool {
+    match (level, cpu_target) {
+        (QuantizationLevel::Int4 { .. }, CpuTarget::RaspberryPi4) => true,
+        (QuantizationLevel::Int4 { .. }, CpuTarget::GenericArm) => true,
+        (QuantizationLevel::Int8 { .. }, _) => true,
+        (QuantizationLevel::Fp16, CpuTarget::RaspberryPi5) => true,
+        (QuantizationLevel::Fp16, CpuTarget::IntelN100) => true,
+        (QuantizationLevel::Fp32, CpuTarget::IntelN100) => true,
+        (QuantizationLevel::Fp32, CpuTarget::GenericX86) => true,
+        _ => false,
+    }
+}
+
 /// CPU-specific optimization targets for inference
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum CpuTarget {
@@ -187,6 +203,22 @@ impl CpuTarget {
             CpuTarget::GenericArm => 2048,
         }
     }
+
+    /// Returns the default worker-thread stack size in bytes for this
+    /// target. Desktop-class targets keep the usual ~2MiB std default;
+    /// memory-constrained embedded boards drop it, mirroring how
+    /// `std::thread` picks `DEFAULT_MIN_STACK_SIZE` per platform rather than
+    /// using one constant everywhere.
+    pub fn default_stack_size_bytes(&self) -> usize {
+        match self {
+            CpuTarget::IntelN100 => 1024 * 1024,
+            CpuTarget::IntelI9_14900HX => 2 * 1024 * 1024,
+            CpuTarget::RaspberryPi4 => 256 * 1024,
+            CpuTarget::RaspberryPi5 => 512 * 1024,
+            CpuTarget::GenericX86 => 2 * 1024 * 1024,
+            CpuTarget::GenericArm => 512 * 1024,
+        }
+    }
 }
 
 impl std::fmt::Display for CpuTarget {
@@ -237,9 +269,26 @@ impl Default for MemoryLimits {
 }
 
 impl MemoryLimits {
-    /// Creates limits based on detected CPU target
+    /// Creates limits based on detected CPU target, clamped to whatever
+    /// memory is actually available: the host's installed RAM, and, inside a
+    /// container, the enclosing cgroup's memory limit if one is set. Without
+    /// this a `CpuTarget::IntelI9_14900HX` container capped at 4GB would
+    /// still advertise the target's full 32GB budget and get OOM-killed well
+    /// before `validate_memory_usage` ever rejects a request.
     pub fn for_cpu_target(cpu: CpuTarget) -> Self {
-        let max_memory_mb = cpu.max_memory_mb();
+        let mut max_memory_mb = cpu.max_memory_mb();
+
+        if let Some(host_mb) = host_total_memory_mb() {
+            max_memory_mb = max_memory_mb.min(host_mb);
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            if let Some(cgroup_mb) = detect_cgroup_memory_limit_mb() {
+                max_memory_mb = max_memory_mb.min(cgroup_mb);
+            }
+        }
+
         Self {
             max_memory_mb,
             max_context_length: 4096,
@@ -253,6 +302,81 @@ impl MemoryLimits {
     }
 }
 
+/// Reads total installed RAM from `/proc/meminfo`'s `MemTotal` line (kB).
+/// `None` off Linux or if the file can't be parsed, in which case the
+/// `CpuTarget`'s static budget is used unclamped.
+#[cfg(target_os = "linux")]
+fn host_total_memory_mb() -> Option<usize> {
+    let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+    let kb: usize = meminfo
+        .lines()
+        .find_map(|line| line.strip_prefix("MemTotal:"))?
+        .trim()
+        .strip_suffix("kB")?
+        .trim()
+        .parse()
+        .ok()?;
+    Some(kb / 1024)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn host_total_memory_mb() -> Option<usize> {
+    None
+}
+
+/// Reads this process's resident set size from `/proc/self/status`'s
+/// `VmRSS` line (kB), the live-usage counterpart to [`host_total_memory_mb`]'s
+/// static capacity reading. `None` off Linux or if the file can't be parsed,
+/// in which case callers such as [`DynamicQuantController`] have no signal
+/// to act on.
+#[cfg(target_os = "linux")]
+pub fn current_rss_mb() -> Option<usize> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let kb: usize = status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmRSS:"))?
+        .trim()
+        .strip_suffix("kB")?
+        .trim()
+        .parse()
+        .ok()?;
+    Some(kb / 1024)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn current_rss_mb() -> Option<usize> {
+    None
+}
+
+/// Reads the enclosing cgroup's memory limit, preferring cgroup v2's unified
+/// `memory.max` (`"max"` means unlimited) and falling back to v1's
+/// `memory.limit_in_bytes` (which reports an implementation-defined huge
+/// value, not a sentinel string, when unset — values above the host's total
+/// RAM are treated as unlimited).
+#[cfg(target_os = "linux")]
+fn detect_cgroup_memory_limit_mb() -> Option<usize> {
+    if let Ok(raw) = std::fs::read_to_string("/sys/fs/cgroup/memory.max") {
+        let raw = raw.trim();
+        if raw == "max" {
+            return None;
+        }
+        return raw.parse::<u64>().ok().map(|bytes| (bytes / (1024 * 1024)) as usize);
+    }
+
+    let bytes: u64 = std::fs::read_to_string("/sys/fs/cgroup/memory/memory.limit_in_bytes")
+        .ok()?
+        .trim()
+        .parse()
+        .ok()?;
+    let mb = bytes / (1024 * 1024);
+    let host_mb = host_total_memory_mb().map(|mb| mb as u64).unwrap_or(u64::MAX);
+    if mb >= host_mb {
+        None
+    } else {
+        Some(mb as usize)
+    }
+}
+
 /// Cloud provider configuration for hybrid inference
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CloudConfig {
@@ -262,9 +386,17 @@ pub struct CloudConfig {
     pub base_url: Option<String>,
     pub timeout_seconds: u64,
     pub max_tokens: usize,
+    /// Extra attempts (beyond the first) the cloud dispatch path spends on
+    /// transient failures (HTTP 429/5xx, timeouts) before giving up.
+    #[serde(default = "CloudConfig::default_retries")]
+    pub retries: u8,
 }
 
 impl CloudConfig {
+    fn default_retries() -> u8 {
+        2
+    }
+
     /// Creates a new OpenAI configuration
     pub fn openai(api_key: String, model_name: String) -> Self {
         Self {
@@ -274,6 +406,7 @@ impl CloudConfig {
             base_url: None,
             timeout_seconds: 30,
             max_tokens: 2048,
+            retries: Self::default_retries(),
         }
     }
 
@@ -286,6 +419,217 @@ impl CloudConfig {
             base_url: None,
             timeout_seconds: 30,
             max_tokens: 2048,
+            retries: Self::default_retries(),
         }
     }
 }
+
+impl QuantizationLevel {
+    /// Steps one rung down the `Fp32 -> Fp16 -> Int8 -> Int4` precision
+    /// ladder, keeping the current group size for grouped levels. Returns
+    /// `None` once already at `Int4` (the bottom of the ladder).
+    pub fn step_down(&self) -> Option<QuantizationLevel> {
+        let group_size = self.group_size().unwrap_or(64);
+        match self {
+            QuantizationLevel::Fp32 => Some(QuantizationLevel::Fp16),
+            QuantizationLevel::Fp16 => Some(QuantizationLevel::Int8 { group_size }),
+            QuantizationLevel::Int8 { group_size } => Some(QuantizationLevel::Int4 {
+                group_size: *group_size,
+            }),
+            QuantizationLevel::Int4 { .. } => None,
+        }
+    }
+
+    /// Steps one rung up the precision ladder, the inverse of [`step_down`](Self::step_down).
+    /// Returns `None` once already at `Fp32` (the top of the ladder).
+    pub fn step_up(&self) -> Option<QuantizationLevel> {
+        let group_size = self.group_size().unwrap_or(64);
+        match self {
+            QuantizationLevel::Int4 { group_size } => Some(QuantizationLevel::Int8 {
+                group_size: *group_size,
+            }),
+            QuantizationLevel::Int8 { .. } => Some(QuantizationLevel::Fp16),
+            QuantizationLevel::Fp16 => Some(QuantizationLevel::Fp32),
+            QuantizationLevel::Fp32 => None,
+        }
+    }
+}
+
+/// Number of consecutive over/under-threshold samples required before
+/// [`DynamicQuantController`] acts, to prevent oscillation around the
+/// threshold.
+const DEFAULT_HYSTERESIS_SAMPLES: u32 = 3;
+
+/// Memory-pressure-driven controller that steps the active quantization
+/// level down the `Fp32 -> Fp16 -> Int8 -> Int4` ladder when resident memory
+/// sustains above `downgrade_ratio * MemoryLimits.max_memory_mb`, and steps
+/// it back up once pressure has sustainably subsided.
+///
+/// Only transitions that [`is_quantization_compatible`] allows for the
+/// current `CpuTarget` are considered; `tick` otherwise holds the current
+/// level steady.
+#[derive(Debug, Clone)]
+pub struct DynamicQuantController {
+    current: QuantizationLevel,
+    cpu_target: CpuTarget,
+    max_memory_mb: usize,
+    downgrade_ratio: f32,
+    upgrade_ratio: f32,
+    hysteresis_samples: u32,
+    consecutive_over: u32,
+    consecutive_under: u32,
+}
+
+impl DynamicQuantController {
+    /// Creates a controller starting at `initial`, downgrading once usage
+    /// sustains above `downgrade_ratio * max_memory_mb` and upgrading back
+    /// once it sustains below a lower `upgrade_ratio * max_memory_mb`
+    /// (the gap between the two ratios is the hysteresis band).
+    pub fn new(
+        initial: QuantizationLevel,
+        cpu_target: CpuTarget,
+        max_memory_mb: usize,
+        downgrade_ratio: f32,
+    ) -> Self {
+        Self {
+            current: initial,
+            cpu_target,
+            max_memory_mb,
+            downgrade_ratio,
+            upgrade_ratio: (downgrade_ratio * 0.75).max(0.0),
+            hysteresis_samples: DEFAULT_HYSTERESIS_SAMPLES,
+            consecutive_over: 0,
+            consecutive_under: 0,
+        }
+    }
+
+    /// Overrides the default number of consecutive samples required before
+    /// acting on sustained pressure.
+    pub fn with_hysteresis_samples(mut self, samples: u32) -> Self {
+        self.hysteresis_samples = samples.max(1);
+        self
+    }
+
+    /// The quantization level currently in effect.
+    pub fn current_level(&self) -> QuantizationLevel {
+        self.current
+    }
+
+    /// Feeds in the latest resident-memory sample (in MB) and returns
+    /// `Some(new_level)` if this tick caused a transition.
+    pub fn tick(&mut self, current_usage_mb: usize) -> Option<QuantizationLevel> {
+        let downgrade_threshold = self.downgrade_ratio * self.max_memory_mb as f32;
+        let upgrade_threshold = self.upgrade_ratio * self.max_memory_mb as f32;
+        let usage = current_usage_mb as f32;
+
+        if usage >= downgrade_threshold {
+            self.consecutive_over += 1;
+            self.consecutive_under = 0;
+        } else if usage <= upgrade_threshold {
+            self.consecutive_under += 1;
+            self.consecutive_over = 0;
+        } else {
+            // Inside the hysteresis band: neither direction accrues.
+            self.consecutive_over = 0;
+            self.consecutive_under = 0;
+        }
+
+        if self.consecutive_over >= self.hysteresis_samples {
+            self.consecutive_over = 0;
+            if let Some(lower) = self.current.step_down() {
+                if is_quantization_compatible(lower, self.cpu_target) {
+                    log::warn!(
+                        "Memory pressure ({current_usage_mb}MB >= {downgrade_threshold:.0}MB): \
+                         downgrading quantization {} -> {}",
+                        self.current,
+                        lower
+                    );
+                    self.current = lower;
+                    return Some(lower);
+                }
+            }
+        } else if self.consecutive_under >= self.hysteresis_samples {
+            self.consecutive_under = 0;
+            if let Some(higher) = self.current.step_up() {
+                if is_quantization_compatible(higher, self.cpu_target) {
+                    log::info!(
+                        "Memory pressure relieved ({current_usage_mb}MB <= {upgrade_threshold:.0}MB): \
+                         upgrading quantization {} -> {}",
+                        self.current,
+                        higher
+                    );
+                    self.current = higher;
+                    return Some(higher);
+                }
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_step_down_ladder() {
+        assert_eq!(QuantizationLevel::Fp32.step_down(), Some(QuantizationLevel::Fp16));
+        assert_eq!(
+            QuantizationLevel::Fp16.step_down(),
+            Some(QuantizationLevel::Int8 { group_size: 64 })
+        );
+        assert_eq!(
+            QuantizationLevel::Int8 { group_size: 32 }.step_down(),
+            Some(QuantizationLevel::Int4 { group_size: 32 })
+        );
+        assert_eq!(QuantizationLevel::Int4 { group_size: 64 }.step_down(), None);
+    }
+
+    #[test]
+    fn test_controller_downgrades_after_sustained_pressure() {
+        let mut controller = DynamicQuantController::new(
+            QuantizationLevel::Fp32,
+            CpuTarget::IntelN100,
+            1000,
+            0.8,
+        )
+        .with_hysteresis_samples(2);
+
+        assert_eq!(controller.tick(900), None); // 1 over, not yet enough
+        let changed = controller.tick(900); // 2 over, should downgrade
+        assert_eq!(changed, Some(QuantizationLevel::Fp16));
+    }
+
+    #[test]
+    fn test_controller_does_not_oscillate_on_single_sample() {
+        let mut controller = DynamicQuantController::new(
+            QuantizationLevel::Fp32,
+            CpuTarget::GenericX86,
+            1000,
+            0.8,
+        )
+        .with_hysteresis_samples(3);
+
+        assert_eq!(controller.tick(900), None);
+        assert_eq!(controller.tick(100), None); // relieves pressure, resets counter
+        assert_eq!(controller.current_level(), QuantizationLevel::Fp32);
+    }
+
+    #[test]
+    fn test_controller_recommends_downgrade_on_single_sample_hysteresis() {
+        // Matches how `run_inference`'s one-shot pre-generation guard
+        // configures its controller: a single over-budget sample must be
+        // enough to recommend a downgrade, since there is no per-token loop
+        // to accumulate further samples before generation starts.
+        let mut controller = DynamicQuantController::new(
+            QuantizationLevel::Fp32,
+            CpuTarget::IntelN100,
+            1000,
+            0.8,
+        )
+        .with_hysteresis_samples(1);
+
+        assert_eq!(controller.tick(900), Some(QuantizationLevel::Fp16));
+    }
+}
@@ -0,0 +1,267 @@
+//! KV-cache session persistence, so a conversation can be paused and resumed
+//! without replaying the whole prompt (`save_session` / `load_session`,
+//! analogous to `stateSize`/`saveState`/`restoreState` in other engines).
+
+use std::fs::File;
+use std::hash::{Hash, Hasher};
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+use crate::configuration::ModelConfig;
+use crate::extended_transformer::ExtendedTransformer;
+use crate::quantization::QuantizationLevel;
+
+/// Magic bytes identifying a session snapshot file.
+const SESSION_MAGIC: [u8; 4] = *b"QSES";
+/// Current on-disk session format version.
+const SESSION_VERSION: u32 = 1;
+
+/// On-disk session header, written before the raw KV-cache bytes.
+#[derive(Debug, Clone, Copy)]
+struct SessionHeader {
+    version: u32,
+    n_layers: u32,
+    seq_len: u32,
+    dim: u32,
+    n_kv_heads: u32,
+    config_hash: u64,
+    quantization_tag: u8,
+    quantization_group_size: u32,
+    pos: u32,
+}
+
+impl SessionHeader {
+    const ENCODED_SIZE: usize = 4 + 4 * 7 + 8 + 1;
+
+    fn write_to(&self, w: &mut impl Write) -> Result<()> {
+        w.write_all(&SESSION_MAGIC)?;
+        w.write_all(&self.version.to_le_bytes())?;
+        w.write_all(&self.n_layers.to_le_bytes())?;
+        w.write_all(&self.seq_len.to_le_bytes())?;
+        w.write_all(&self.dim.to_le_bytes())?;
+        w.write_all(&self.n_kv_heads.to_le_bytes())?;
+        w.write_all(&self.config_hash.to_le_bytes())?;
+        w.write_all(&[self.quantization_tag])?;
+        w.write_all(&self.quantization_group_size.to_le_bytes())?;
+        w.write_all(&self.pos.to_le_bytes())?;
+        Ok(())
+    }
+
+    fn read_from(r: &mut impl Read) -> Result<Self> {
+        let mut magic = [0u8; 4];
+        r.read_exact(&mut magic)?;
+        if magic != SESSION_MAGIC {
+            anyhow::bail!("Not a pico-qwen session file (bad magic)");
+        }
+
+        let mut u32buf = [0u8; 4];
+        let mut read_u32 = |r: &mut dyn Read| -> Result<u32> {
+            r.read_exact(&mut u32buf)?;
+            Ok(u32::from_le_bytes(u32buf))
+        };
+
+        let version = read_u32(r)?;
+        if version != SESSION_VERSION {
+            anyhow::bail!(
+                "Unsupported session format version: expected {SESSION_VERSION}, got {version}"
+            );
+        }
+
+        let n_layers = read_u32(r)?;
+        let seq_len = read_u32(r)?;
+        let dim = read_u32(r)?;
+        let n_kv_heads = read_u32(r)?;
+
+        let mut hash_buf = [0u8; 8];
+        r.read_exact(&mut hash_buf)?;
+        let config_hash = u64::from_le_bytes(hash_buf);
+
+        let mut tag_buf = [0u8; 1];
+        r.read_exact(&mut tag_buf)?;
+        let quantization_tag = tag_buf[0];
+
+        let quantization_group_size = read_u32(r)?;
+        let pos = read_u32(r)?;
+
+        Ok(Self {
+            version,
+            n_layers,
+            seq_len,
+            dim,
+            n_kv_heads,
+            config_hash,
+            quantization_tag,
+            quantization_group_size,
+            pos,
+        })
+    }
+}
+
+/// Hashes the layout-relevant fields of `ModelConfig` so a restore into an
+/// incompatible model is rejected with a clear error rather than corrupting
+/// memory.
+fn hash_model_config(config: &ModelConfig) -> u64 {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    config.dim.hash(&mut hasher);
+    config.hidden_dim.hash(&mut hasher);
+    config.n_layers.hash(&mut hasher);
+    config.n_heads.hash(&mut hasher);
+    config.n_kv_heads.hash(&mut hasher);
+    config.head_dim.hash(&mut hasher);
+    config.seq_len.hash(&mut hasher);
+    config.vocab_size.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn quantization_tag(level: QuantizationLevel) -> (u8, u32) {
+    match level {
+        QuantizationLevel::Int4 { group_size } => (0, group_size as u32),
+        QuantizationLevel::Int8 { group_size } => (1, group_size as u32),
+        QuantizationLevel::Fp16 => (2, 0),
+        QuantizationLevel::Fp32 => (3, 0),
+    }
+}
+
+impl ExtendedTransformer {
+    /// Serializes the current KV cache, token position, active quantization
+    /// level, and a hash of the base model config to `dest`.
+    pub fn save_session(&self, dest: &Path) -> Result<()> {
+        let base = &self.config.base;
+        let (quantization_tag, quantization_group_size) = quantization_tag(self.config.quantization);
+
+        let header = SessionHeader {
+            version: SESSION_VERSION,
+            n_layers: base.n_layers as u32,
+            seq_len: base.seq_len as u32,
+            dim: base.dim as u32,
+            n_kv_heads: base.n_kv_heads as u32,
+            config_hash: hash_model_config(base),
+            quantization_tag,
+            quantization_group_size,
+            pos: self.transformer.pos() as u32,
+        };
+
+        let file = File::create(dest)
+            .with_context(|| format!("Failed to create session file: {dest:?}"))?;
+        let mut writer = BufWriter::new(file);
+
+        header.write_to(&mut writer)?;
+        writer
+            .write_all(self.transformer.kv_cache_bytes())
+            .context("Failed to write KV cache bytes")?;
+        writer.flush().context("Failed to flush session file")?;
+
+        Ok(())
+    }
+
+    /// Restores a KV cache previously written by [`save_session`](Self::save_session).
+    /// Rejects snapshots whose layout or config hash don't match the
+    /// currently loaded model.
+    pub fn load_session(&mut self, src: &Path) -> Result<()> {
+        let file =
+            File::open(src).with_context(|| format!("Failed to open session file: {src:?}"))?;
+        let mut reader = BufReader::new(file);
+
+        let header = SessionHeader::read_from(&mut reader)
+            .with_context(|| format!("Failed to read session header: {src:?}"))?;
+
+        let base = &self.config.base;
+        if header.n_layers as usize != base.n_layers
+            || header.seq_len as usize != base.seq_len
+            || header.dim as usize != base.dim
+            || header.n_kv_heads as usize != base.n_kv_heads
+        {
+            anyhow::bail!(
+                "Session layout mismatch: file is (n_layers={}, seq_len={}, dim={}, n_kv_heads={}), \
+                 model is (n_layers={}, seq_len={}, dim={}, n_kv_heads={})",
+                header.n_layers, header.seq_len, header.dim, header.n_kv_heads,
+                base.n_layers, base.seq_len, base.dim, base.n_kv_heads
+            );
+        }
+
+        if header.config_hash != hash_model_config(base) {
+            anyhow::bail!(
+                "Session was saved against a different model configuration; refusing to restore"
+            );
+        }
+
+        // `pos` isn't part of `config_hash`, so a matching hash doesn't rule
+        // out a hand-edited or corrupted out-of-range value here; bounds-check
+        // it explicitly before it reaches `set_pos`.
+        if header.pos as usize > base.seq_len {
+            anyhow::bail!(
+                "Session position {} exceeds model seq_len {}; refusing to restore",
+                header.pos,
+                base.seq_len
+            );
+        }
+
+        let mut kv_bytes = vec![0u8; self.transformer.kv_cache_bytes().len()];
+        reader
+            .read_exact(&mut kv_bytes)
+            .context("Session file is truncated or KV cache size mismatches the model")?;
+
+        self.transformer.kv_cache_bytes_mut().copy_from_slice(&kv_bytes);
+        self.transformer.set_pos(header.pos as usize);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configuration::ModelConfig;
+
+    fn sample_config() -> ModelConfig {
+        ModelConfig {
+            dim: 1024,
+            hidden_dim: 4096,
+            n_layers: 12,
+            n_heads: 16,
+            n_kv_heads: 4,
+            head_dim: 64,
+            seq_len: 2048,
+            vocab_size: 32000,
+            group_size: 64,
+            shared_classifier: true,
+            rope_theta: 10000.0,
+        }
+    }
+
+    #[test]
+    fn test_config_hash_is_stable_and_layout_sensitive() {
+        let a = sample_config();
+        let mut b = sample_config();
+        assert_eq!(hash_model_config(&a), hash_model_config(&b));
+
+        b.n_layers += 1;
+        assert_ne!(hash_model_config(&a), hash_model_config(&b));
+    }
+
+    #[test]
+    fn test_header_roundtrip() {
+        let header = SessionHeader {
+            version: SESSION_VERSION,
+            n_layers: 12,
+            seq_len: 2048,
+            dim: 1024,
+            n_kv_heads: 4,
+            config_hash: 0xdead_beef_cafe_babe,
+            quantization_tag: 1,
+            quantization_group_size: 64,
+            pos: 17,
+        };
+
+        let mut buf = Vec::new();
+        header.write_to(&mut buf).unwrap();
+        assert_eq!(buf.len(), SessionHeader::ENCODED_SIZE);
+
+        let mut cursor = std::io::Cursor::new(buf);
+        let decoded = SessionHeader::read_from(&mut cursor).unwrap();
+        assert_eq!(decoded.pos, 17);
+        assert_eq!(decoded.config_hash, header.config_hash);
+    }
+}
@@ -0,0 +1,74 @@
+use anyhow::{Context, Result};
+use memmap2::Mmap;
+use std::fs::File;
+
+/// Thin, bounds-checked cursor over a memory-mapped checkpoint file.
+/// Sequential reads via [`get_bytes`](Self::get_bytes)/[`skip`](Self::skip)
+/// advance an internal offset; every read is checked against the mapping's
+/// length rather than trusting the file to be well-formed, so a truncated
+/// `.bin` fails with a descriptive error instead of an out-of-bounds read.
+pub struct MemoryMapper {
+    mmap: Mmap,
+    offset: usize,
+}
+
+impl MemoryMapper {
+    /// Memory-maps `file` for reading. `Mmap::map` only borrows `file`, which
+    /// is dropped when this returns; the mapping stays valid without it.
+    pub fn new(file: File) -> Result<Self> {
+        // Safety: the mapped file is only read, never written concurrently
+        // by this process; `memmap2` can't rule out external modification,
+        // which is the same caveat every mmap-based loader accepts.
+        let mmap = unsafe { Mmap::map(&file) }.context("Failed to memory-map checkpoint file")?;
+        Ok(Self { mmap, offset: 0 })
+    }
+
+    /// Returns the next `len` bytes and advances the cursor past them.
+    pub fn get_bytes(&mut self, len: usize) -> Result<&[u8]> {
+        let end = self
+            .offset
+            .checked_add(len)
+            .context("Offset overflow while reading checkpoint")?;
+        if end > self.mmap.len() {
+            anyhow::bail!(
+                "Attempted to read {} bytes at offset {}, but file is only {} bytes",
+                len,
+                self.offset,
+                self.mmap.len()
+            );
+        }
+        let bytes = &self.mmap[self.offset..end];
+        self.offset = end;
+        Ok(bytes)
+    }
+
+    /// Advances the cursor by `len` bytes without returning them, e.g. to
+    /// skip header padding.
+    pub fn skip(&mut self, len: usize) -> Result<()> {
+        let end = self
+            .offset
+            .checked_add(len)
+            .context("Offset overflow while skipping checkpoint bytes")?;
+        if end > self.mmap.len() {
+            anyhow::bail!(
+                "Attempted to skip past end of file: offset {} + {} > {}",
+                self.offset,
+                len,
+                self.mmap.len()
+            );
+        }
+        self.offset = end;
+        Ok(())
+    }
+
+    /// Current byte offset of the read cursor.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// The full mapped file, for callers that need direct slice access
+    /// (e.g. the rkyv weight-table loader) rather than sequential reads.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.mmap
+    }
+}
@@ -0,0 +1,89 @@
+use anyhow::{Context, Result};
+use rkyv::{Archive, Deserialize, Serialize};
+
+/// Element type of a tensor's raw bytes, so the reader knows how to
+/// reinterpret them without hard-coding per-tensor layout assumptions.
+#[derive(Debug, Clone, Copy, Archive, Serialize, Deserialize)]
+#[archive(check_bytes)]
+pub enum TensorDType {
+    F32,
+    F16,
+    Int8,
+    Int4,
+}
+
+/// Describes one tensor's placement inside the weight region: a byte
+/// offset/length pair relative to the start of the weights, plus enough
+/// shape/dtype metadata for the reader to reinterpret the bytes.
+#[derive(Debug, Clone, Archive, Serialize, Deserialize)]
+#[archive(check_bytes)]
+pub struct TensorDescriptor {
+    pub name: String,
+    pub offset: u64,
+    pub length: u64,
+    pub shape: Vec<u32>,
+    pub dtype: TensorDType,
+}
+
+/// Self-describing table of every tensor in the weight region, rkyv-archived
+/// so it can be validated and read with zero copy straight out of the
+/// memory-mapped checkpoint file.
+#[derive(Debug, Clone, Archive, Serialize, Deserialize)]
+#[archive(check_bytes)]
+pub struct WeightTable {
+    pub tensors: Vec<TensorDescriptor>,
+}
+
+/// Casts `region` into an `&ArchivedWeightTable` after rkyv's `bytecheck`
+/// validates the archive is well-formed, then separately validates every
+/// tensor's `offset + length` lies within `weights_len`. Both checks must
+/// pass before any `&Archived<Tensor>` is handed back, so a truncated or
+/// hand-edited `.bin` is rejected with a descriptive error instead of
+/// triggering undefined behavior when the engine later indexes into it.
+///
+/// Only `qwen3-cli`'s `inspect_checkpoint` diagnostic calls this (via
+/// `configuration::read_weight_table`). `transformer::TransformerBuilder` —
+/// the real model-loading path the original unchecked-pointer-arithmetic
+/// complaint was about — does not exist anywhere in this crate (`mod
+/// transformer` in `lib.rs` has no backing file), so there is no runtime
+/// loading path for this reader to replace yet; treat this as
+/// diagnostic-only tooling, not a hardening of model loading, until
+/// `TransformerBuilder` exists and is wired to call it.
+pub fn read_weight_table(region: &[u8], weights_len: u64) -> Result<&ArchivedWeightTable> {
+    let table = rkyv::check_archived_root::<WeightTable>(region)
+        .map_err(|e| anyhow::anyhow!("Weight table failed rkyv validation: {e}"))?;
+
+    for tensor in table.tensors.iter() {
+        let end = tensor
+            .offset
+            .checked_add(tensor.length)
+            .with_context(|| format!("Tensor '{}' offset+length overflowed", tensor.name))?;
+        if end > weights_len {
+            anyhow::bail!(
+                "Tensor '{}' spans bytes {}..{}, beyond the {}-byte weight region",
+                tensor.name,
+                tensor.offset,
+                end,
+                weights_len
+            );
+        }
+    }
+
+    Ok(table)
+}
+
+/// Returns a validated tensor's raw bytes, bounds-checking against the
+/// actual weight-region slice a second time (not just the length recorded
+/// by [`read_weight_table`]) before handing out a reference.
+pub fn tensor_bytes<'a>(
+    weights: &'a [u8],
+    tensor: &ArchivedTensorDescriptor,
+) -> Result<&'a [u8]> {
+    let start = tensor.offset as usize;
+    let end = start
+        .checked_add(tensor.length as usize)
+        .with_context(|| format!("Tensor '{}' offset+length overflowed", tensor.name))?;
+    weights
+        .get(start..end)
+        .with_context(|| format!("Tensor '{}' is out of bounds of the weight region", tensor.name))
+}
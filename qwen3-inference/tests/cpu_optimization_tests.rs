@@ -241,6 +241,7 @@ mod tests {
         assert!(strategy.simd_width * 4 == strategy.alignment);
         assert!(strategy.use_avx512 == cpu_info.has_feature(CpuFeature::Avx512F));
         assert!(strategy.use_fma == cpu_info.has_feature(CpuFeature::Fma));
+        assert!(strategy.use_vnni == cpu_info.has_feature(CpuFeature::Vnni));
 
         // Cache blocking should be aligned
         let cache_blocking = &strategy.cache_blocking;
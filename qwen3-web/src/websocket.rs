@@ -8,9 +8,36 @@ use axum::{
 use futures::{sink::SinkExt, stream::StreamExt};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use thiserror::Error;
+use tokio::sync::mpsc;
 
 use crate::config::Config;
 
+/// Typed counterpart to the ad-hoc `{"message": "..."}` error payloads this
+/// module used to send, giving clients a stable `code` to branch on instead
+/// of the English `message`. Mirrors the `code`/status taxonomy of
+/// `qwen3-api`'s `ApiError` so the two layers report errors the same way,
+/// without pulling in a cross-crate dependency neither otherwise needs.
+#[derive(Debug, Error)]
+enum WsError {
+    #[error("bad request: {0}")]
+    BadRequest(String),
+    #[error("upstream unavailable: {0}")]
+    UpstreamUnavailable(String),
+    #[error("internal error: {0}")]
+    Internal(String),
+}
+
+impl WsError {
+    fn code(&self) -> &'static str {
+        match self {
+            WsError::BadRequest(_) => "bad_request",
+            WsError::UpstreamUnavailable(_) => "upstream_unavailable",
+            WsError::Internal(_) => "internal_error",
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ChatMessage {
     pub role: String,
@@ -38,64 +65,206 @@ pub async fn websocket_handler(ws: WebSocketUpgrade, State(config): State<Config
 
 async fn handle_websocket(socket: WebSocket, config: Config) {
     let (mut sender, mut receiver) = socket.split();
+    let (tx, mut rx) = mpsc::unbounded_channel::<Message>();
+
+    // Aborted on a fresh "chat" (one generation per socket at a time), an
+    // explicit "cancel", or the client closing the socket, and left to
+    // finish on its own otherwise; nothing
+    // ever joins it, since its only observable effect is the messages it
+    // pushes onto `tx`.
+    let mut generation: Option<tokio::task::AbortHandle> = None;
 
-    while let Some(Ok(msg)) = receiver.next().await {
-        match msg {
-            Message::Text(text) => {
-                if let Ok(request) = serde_json::from_str::<WebSocketRequest>(&text) {
-                    match request.action.as_str() {
-                        "ping" => {
-                            let timestamp = std::time::SystemTime::now()
-                                .duration_since(std::time::UNIX_EPOCH)
-                                .unwrap()
-                                .as_secs();
-                            let _ = sender
-                                .send(Message::Text(
-                                    serde_json::to_string(&WebSocketResponse {
-                                        action: "pong".to_string(),
-                                        data: json!({"timestamp": timestamp}),
-                                    })
-                                    .unwrap(),
-                                ))
-                                .await;
+    loop {
+        tokio::select! {
+            incoming = receiver.next() => {
+                match incoming {
+                    Some(Ok(Message::Text(text))) => {
+                        let Ok(request) = serde_json::from_str::<WebSocketRequest>(&text) else {
+                            continue;
+                        };
+
+                        match request.action.as_str() {
+                            "ping" => {
+                                let timestamp = std::time::SystemTime::now()
+                                    .duration_since(std::time::UNIX_EPOCH)
+                                    .unwrap()
+                                    .as_secs();
+                                let _ = tx.send(response_message(
+                                    "pong",
+                                    json!({"timestamp": timestamp}),
+                                ));
+                            }
+                            "chat" => {
+                                if let Some(handle) = generation.take() {
+                                    handle.abort();
+                                }
+                                let task = tokio::spawn(stream_chat(
+                                    config.clone(),
+                                    request,
+                                    tx.clone(),
+                                ));
+                                generation = Some(task.abort_handle());
+                            }
+                            "cancel" => {
+                                if let Some(handle) = generation.take() {
+                                    handle.abort();
+                                }
+                            }
+                            _ => {
+                                let _ = tx.send(error_message(WsError::BadRequest(
+                                    "Unknown action".to_string(),
+                                )));
+                            }
                         }
-                        "chat" => {
-                            // Forward to API server
-                            let response = forward_to_api(&config, &request).await;
-                            let _ = sender
-                                .send(Message::Text(serde_json::to_string(&response).unwrap()))
-                                .await;
+                    }
+                    Some(Ok(Message::Close(_))) | None => {
+                        if let Some(handle) = generation.take() {
+                            handle.abort();
                         }
-                        _ => {
-                            let _ = sender
-                                .send(Message::Text(
-                                    serde_json::to_string(&WebSocketResponse {
-                                        action: "error".to_string(),
-                                        data: json!({"message": "Unknown action"}),
-                                    })
-                                    .unwrap(),
-                                ))
-                                .await;
+                        break;
+                    }
+                    Some(Ok(_)) => {}
+                    Some(Err(_)) => break,
+                }
+            }
+            outgoing = rx.recv() => {
+                match outgoing {
+                    Some(msg) => {
+                        if sender.send(msg).await.is_err() {
+                            break;
                         }
                     }
+                    None => break,
                 }
             }
-            Message::Close(_) => break,
-            _ => {}
         }
     }
 }
 
-async fn forward_to_api(_config: &Config, _request: &WebSocketRequest) -> WebSocketResponse {
-    // For now, return a mock response
-    // In real implementation, this would make HTTP requests to the API server
-    WebSocketResponse {
-        action: "chat_response".to_string(),
-        data: json!({
-            "message": {
-                "role": "assistant",
-                "content": "This is a mock response. Connect to a real API server for actual responses."
+/// Streams one `chat` request to the API server's `/api/v1/generate`
+/// (`stream: true`) and relays each decoded token as a `chat_chunk`,
+/// finishing with a `chat_done` carrying the finish reason and usage, or an
+/// `error` response if the upstream request or stream fails. Dropped (via
+/// the caller's `AbortHandle`) rather than returning a value on client
+/// cancellation mid-stream.
+async fn stream_chat(config: Config, request: WebSocketRequest, tx: mpsc::UnboundedSender<Message>) {
+    let Some(prompt) = request.message.as_ref().map(|m| m.content.clone()) else {
+        let _ = tx.send(error_message(WsError::BadRequest(
+            "chat action requires a message".to_string(),
+        )));
+        return;
+    };
+
+    let client = reqwest::Client::new();
+    let body = json!({
+        "model": request.model.unwrap_or_else(|| "default".to_string()),
+        "prompt": prompt,
+        "max_tokens": request.max_tokens,
+        "temperature": request.temperature,
+        "stream": true,
+    });
+
+    let response = match client
+        .post(format!("{}/api/v1/generate", config.api.url))
+        .json(&body)
+        .send()
+        .await
+    {
+        Ok(response) => response,
+        Err(err) => {
+            let _ = tx.send(error_message(WsError::UpstreamUnavailable(format!(
+                "Failed to reach API server: {err}"
+            ))));
+            return;
+        }
+    };
+
+    let mut byte_stream = response.bytes_stream();
+    let mut buffer = String::new();
+    let mut index = 0usize;
+
+    while let Some(chunk) = byte_stream.next().await {
+        let chunk = match chunk {
+            Ok(chunk) => chunk,
+            Err(err) => {
+                let _ = tx.send(error_message(WsError::Internal(format!(
+                    "Stream error: {err}"
+                ))));
+                return;
             }
-        }),
+        };
+        buffer.push_str(&String::from_utf8_lossy(&chunk));
+
+        while let Some(boundary) = buffer.find("\n\n") {
+            let frame = buffer[..boundary].to_string();
+            buffer.drain(..boundary + 2);
+
+            let (event, data) = parse_sse_frame(&frame);
+            match event.as_deref() {
+                Some("done") => {
+                    let usage = serde_json::from_str::<serde_json::Value>(&data)
+                        .ok()
+                        .and_then(|v| v.get("usage").cloned())
+                        .unwrap_or(serde_json::Value::Null);
+                    let _ = tx.send(response_message(
+                        "chat_done",
+                        json!({"finish_reason": "stop", "usage": usage}),
+                    ));
+                    return;
+                }
+                Some("error") => {
+                    let _ = tx.send(error_message(WsError::UpstreamUnavailable(data)));
+                    return;
+                }
+                _ => {
+                    let Ok(token) = serde_json::from_str::<serde_json::Value>(&data) else {
+                        continue;
+                    };
+                    let delta = token.get("delta").and_then(|v| v.as_str()).unwrap_or("");
+                    let _ = tx.send(response_message(
+                        "chat_chunk",
+                        json!({"delta": delta, "index": index}),
+                    ));
+                    index += 1;
+                }
+            }
+        }
     }
 }
+
+/// Splits one SSE frame (everything between a `"\n\n"` pair) into its
+/// `event:` name, if any, and its `data:` payload, concatenating multiple
+/// `data:` lines with `\n` per the SSE spec.
+fn parse_sse_frame(frame: &str) -> (Option<String>, String) {
+    let mut event = None;
+    let mut data_lines = Vec::new();
+
+    for line in frame.lines() {
+        if let Some(rest) = line.strip_prefix("event:") {
+            event = Some(rest.trim().to_string());
+        } else if let Some(rest) = line.strip_prefix("data:") {
+            data_lines.push(rest.trim_start().to_string());
+        }
+    }
+
+    (event, data_lines.join("\n"))
+}
+
+/// Wraps a [`WsError`] in the `{"error": {"code", "message"}}` shape shared
+/// with `qwen3-api`'s HTTP error responses, sent as an `error` action.
+fn error_message(err: WsError) -> Message {
+    response_message(
+        "error",
+        json!({"error": {"code": err.code(), "message": err.to_string()}}),
+    )
+}
+
+fn response_message(action: &str, data: serde_json::Value) -> Message {
+    Message::Text(
+        serde_json::to_string(&WebSocketResponse {
+            action: action.to_string(),
+            data,
+        })
+        .unwrap(),
+    )
+}